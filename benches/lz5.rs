@@ -0,0 +1,51 @@
+#![feature(test)]
+
+extern crate test;
+use test::Bencher;
+use zen::compress::{lz5_compress, lz5_compress_exhaustive};
+
+/// Synthetic data resembling level data: runs of the same block, repeated rows and noise.
+fn synthetic_level_data(number_of_bytes: usize) -> Vec<u8> {
+    let mut seed: u32 = 0x1234_5678;
+    let mut data = Vec::with_capacity(number_of_bytes);
+
+    while data.len() < number_of_bytes {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        let run = (seed >> 24) as usize % 0x40 + 1;
+        match (seed >> 16) % 4 {
+            0 => data.extend(vec![(seed >> 8) as u8; run]),
+            1 if data.len() > 0x100 => {
+                let start = data.len() - 0x100;
+                data.extend_from_within(start..start + run);
+            }
+            _ => data.extend((0..run).map(|i| (seed >> (i % 24)) as u8)),
+        }
+    }
+
+    data.truncate(number_of_bytes);
+    data
+}
+
+#[bench]
+fn bench_lz5_compress_with_hash_chains(b: &mut Bencher) {
+    let data = synthetic_level_data(0x4000);
+    b.iter(|| lz5_compress(&data));
+}
+
+#[bench]
+fn bench_lz5_compress_exhaustive(b: &mut Bencher) {
+    let data = synthetic_level_data(0x4000);
+    b.iter(|| lz5_compress_exhaustive(&data));
+}
+
+#[bench]
+fn bench_lz5_compress_zeros_with_hash_chains(b: &mut Bencher) {
+    let data = vec![0; 0x1000];
+    b.iter(|| lz5_compress(&data));
+}
+
+#[bench]
+fn bench_lz5_compress_zeros_exhaustive(b: &mut Bencher) {
+    let data = vec![0; 0x1000];
+    b.iter(|| lz5_compress_exhaustive(&data));
+}
@@ -39,6 +39,132 @@ impl From<Pc> for LoRom {
     }
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct HiRom {
+    pub address: usize,
+}
+
+impl From<HiRom> for Pc {
+    fn from(hi_rom: HiRom) -> Self {
+        // Banks $C0-$FF map the whole 4 MB, and all other banks are mirrors of them.
+        Pc {
+            address: hi_rom.address & 0x3F_FFFF,
+        }
+    }
+}
+
+impl From<Pc> for HiRom {
+    fn from(pc: Pc) -> Self {
+        HiRom {
+            address: 0xC0_0000 + pc.address,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ExHiRom {
+    pub address: usize,
+}
+
+impl From<ExHiRom> for Pc {
+    fn from(ex_hi_rom: ExHiRom) -> Self {
+        // Banks $C0-$FF map the first 4 MB, and banks $40-$7D the next ones.
+        // Banks $7E-$7F are RAM, so their last $8000 bytes are only found at $3E-$3F:8000.
+        let extended = if ex_hi_rom.address & 0x80_0000 == 0 {
+            0x40_0000
+        } else {
+            0
+        };
+
+        Pc {
+            address: extended + (ex_hi_rom.address & 0x3F_FFFF),
+        }
+    }
+}
+
+impl From<Pc> for ExHiRom {
+    fn from(pc: Pc) -> Self {
+        let address = match pc.address {
+            0x00_0000..=0x3F_FFFF => 0xC0_0000 + pc.address,
+            0x40_0000..=0x7D_FFFF => pc.address,
+            _ => pc.address - 0x40_0000,
+        };
+
+        ExHiRom { address }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ExLoRom {
+    pub address: usize,
+}
+
+impl From<ExLoRom> for Pc {
+    fn from(ex_lo_rom: ExLoRom) -> Self {
+        // Banks $80-$FF map the first 4 MB, same as LoRom, and banks $00-$7D the next ones.
+        let extended = if ex_lo_rom.address & 0x80_0000 == 0 {
+            0x40_0000
+        } else {
+            0
+        };
+
+        Pc {
+            address: extended
+                + Pc::from(LoRom {
+                    address: ex_lo_rom.address,
+                })
+                .address,
+        }
+    }
+}
+
+impl From<Pc> for ExLoRom {
+    fn from(pc: Pc) -> Self {
+        let address = LoRom::from(Pc {
+            address: pc.address % 0x40_0000,
+        })
+        .address;
+
+        ExLoRom {
+            address: if pc.address < 0x40_0000 {
+                address
+            } else {
+                address & !0x80_0000
+            },
+        }
+    }
+}
+
+/// How SNES addresses are mapped to the ROM, as given by the map mode of its header.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Mapping {
+    #[default]
+    LoRom,
+    HiRom,
+    ExLoRom,
+    ExHiRom,
+}
+
+impl Mapping {
+    pub fn to_pc(&self, address: usize) -> Pc {
+        match self {
+            Mapping::LoRom => LoRom { address }.into(),
+            Mapping::HiRom => HiRom { address }.into(),
+            Mapping::ExLoRom => ExLoRom { address }.into(),
+            Mapping::ExHiRom => ExHiRom { address }.into(),
+        }
+    }
+
+    pub fn from_pc(&self, pc: Pc) -> usize {
+        match self {
+            Mapping::LoRom => LoRom::from(pc).address,
+            Mapping::HiRom => HiRom::from(pc).address,
+            Mapping::ExLoRom => ExLoRom::from(pc).address,
+            Mapping::ExHiRom => ExHiRom::from(pc).address,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -68,4 +194,35 @@ mod tests {
             assert_eq!(LoRom::from(pc), expected_lo_rom);
         }
     }
+
+    /// Convert HiRom, ExHiRom and ExLoRom addresses to 'Pc' and back, including their mirrors.
+    #[test]
+    fn convert_other_mappings_and_pc_address_between_themselves() {
+        #[rustfmt::skip]
+        let addresses = [
+            // Mapping           Original Address  Pc Address  Expected Pc to Mapping Address
+            (Mapping::HiRom,     0xC00000,         0x000000,   0xC00000),
+            (Mapping::HiRom,     0xC12345,         0x012345,   0xC12345),
+            (Mapping::HiRom,     0x408000,         0x008000,   0xC08000),
+            (Mapping::HiRom,     0x3F8000,         0x3F8000,   0xFF8000),
+            (Mapping::HiRom,     0xFFFFFF,         0x3FFFFF,   0xFFFFFF),
+            (Mapping::ExHiRom,   0xC00000,         0x000000,   0xC00000),
+            (Mapping::ExHiRom,   0xFFFFFF,         0x3FFFFF,   0xFFFFFF),
+            (Mapping::ExHiRom,   0x400000,         0x400000,   0x400000),
+            (Mapping::ExHiRom,   0x008000,         0x408000,   0x408000),
+            (Mapping::ExHiRom,   0x7DFFFF,         0x7DFFFF,   0x7DFFFF),
+            (Mapping::ExHiRom,   0x3E8000,         0x7E8000,   0x3E8000),
+            (Mapping::ExLoRom,   0x808000,         0x000000,   0x808000),
+            (Mapping::ExLoRom,   0xFFFFFF,         0x3FFFFF,   0xFFFFFF),
+            (Mapping::ExLoRom,   0x008000,         0x400000,   0x008000),
+            (Mapping::ExLoRom,   0x408000,         0x600000,   0x408000),
+            (Mapping::ExLoRom,   0x7DFFFF,         0x7EFFFF,   0x7DFFFF),
+            (Mapping::LoRom,     0x008000,         0x000000,   0x808000),
+        ];
+
+        for (mapping, original_address, pc, expected_address) in addresses {
+            assert_eq!(mapping.to_pc(original_address), Pc { address: pc });
+            assert_eq!(mapping.from_pc(Pc { address: pc }), expected_address);
+        }
+    }
 }
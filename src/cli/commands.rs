@@ -0,0 +1,233 @@
+use std::{error::Error, fs, path::Path};
+
+use zen::{
+    header,
+    image::{tileset_to_image, RoomImageOptions},
+    patch::{self, bps, ips},
+    project,
+    super_metroid::{
+        self, address::ROOMS_BANK, room::Condition, LoadOptions, RomVersion, SuperMetroid,
+    },
+};
+
+use super::{parse_number, Arguments, UsageError};
+
+type CommandResult = Result<(), Box<dyn Error>>;
+
+fn read(path: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    fs::read(path).map_err(|error| format!("Failed to read {}: {}.", path, error).into())
+}
+
+fn write(path: &str, data: &[u8]) -> CommandResult {
+    fs::write(path, data).map_err(|error| format!("Failed to write {}: {}.", path, error).into())
+}
+
+fn load(path: &str) -> Result<SuperMetroid, Box<dyn Error>> {
+    Ok(super_metroid::load_rom(
+        read(path)?,
+        LoadOptions::default(),
+    )?)
+}
+
+/// Show the header and what was loaded from the ROM.
+pub fn info(arguments: &Arguments) -> CommandResult {
+    arguments.expect_flags(&[])?;
+    let sm = load(&arguments.expect_positional(&["<rom>"])?[0])?;
+
+    println!("Title:       {}", sm.header.title());
+    println!("Version:     {:?}", sm.version);
+    println!("Mapping:     {:?}", sm.mapping);
+    println!("Size:        {} KB", sm.rom.len() / 1024);
+    println!(
+        "Checksum:    ${:04X} ({})",
+        sm.header.checksum,
+        if sm.has_valid_checksum() {
+            "valid"
+        } else {
+            "invalid"
+        }
+    );
+    println!("Copier:      {}", sm.copier_header.is_some());
+    println!("Rooms:       {}", sm.rooms.len());
+    println!("States:      {}", sm.states.len());
+    println!("Doors:       {}", sm.doors.len());
+    println!("PLM lists:   {}", sm.plm_populations.len());
+    println!("Enemy lists: {}", sm.enemy_populations.len());
+    println!("Enemy sets:  {}", sm.enemy_sets.len());
+    println!("Species:     {}", sm.enemy_species.len());
+    println!("FX lists:    {}", sm.fx.len());
+    println!("Level Data:  {}", sm.levels.len());
+    println!("Tilesets:    {}", sm.tilesets.len());
+    println!("Palettes:    {}", sm.palettes.len());
+    println!("Gfx:         {}", sm.graphics.len());
+    println!("Tile Tables: {}", sm.tile_tables.len());
+    println!("Free space:  {} bytes", sm.free_space.total());
+    for diagnostic in sm.diagnostics.iter() {
+        println!("Warning: {}", diagnostic);
+    }
+
+    Ok(())
+}
+
+/// List every Room with its area, size and States.
+pub fn list_rooms(arguments: &Arguments) -> CommandResult {
+    arguments.expect_flags(&[])?;
+    let sm = load(&arguments.expect_positional(&["<rom>"])?[0])?;
+
+    let mut addresses: Vec<&usize> = sm.rooms.keys().collect();
+    addresses.sort_unstable();
+
+    println!("Address  Area Index Size  States");
+    for address in addresses {
+        let room = &sm.rooms[address];
+        println!(
+            "${:06X}  {:>4} {:>5} {:>2}x{:<2} {}",
+            address,
+            room.area,
+            room.index,
+            room.width,
+            room.height,
+            room.state_conditions.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// Render the Room with one of its States, or the default one, optionally with its enemies and liquid.
+pub fn render_room(arguments: &Arguments) -> CommandResult {
+    arguments.expect_flags(&["--enemies", "--liquid"])?;
+    let positional = arguments.expect_positional(&["<rom>", "<room>"])?;
+    let output = arguments.required_option("--output")?;
+    let sm = load(&positional[0])?;
+
+    let mut address = parse_number(&positional[1])?;
+    if address <= 0xFFFF {
+        address += ROOMS_BANK;
+    }
+    let room = sm
+        .rooms
+        .get(&address)
+        .ok_or_else(|| format!("There is no Room at ${:06X}.", address))?;
+
+    let state_condition = match arguments.option("--state") {
+        Some(index) => room
+            .state_conditions
+            .get(parse_number(index)?)
+            .ok_or_else(|| UsageError(format!("Room has no State {}.", index)))?,
+        None => room
+            .state_conditions
+            .iter()
+            .find(|state_condition| state_condition.condition == Condition::Default)
+            .ok_or("Room has no default State.")?,
+    };
+    let state = &sm.states[&(state_condition.state_address as usize)];
+    if !sm.levels.contains_key(&(state.level_address as usize)) {
+        return Err(format!("Level Data ${:06X} failed to load.", state.level_address).into());
+    }
+
+    let options = RoomImageOptions {
+        enemies: arguments.flag("--enemies"),
+        liquid: arguments.flag("--liquid"),
+    };
+    sm.room_to_image_with(room, state, options)
+        .save(output)
+        .map_err(|error| format!("Failed to write {}: {}.", output, error))?;
+    Ok(())
+}
+
+/// Render the Tileset with the given index.
+pub fn render_tileset(arguments: &Arguments) -> CommandResult {
+    arguments.expect_flags(&[])?;
+    let positional = arguments.expect_positional(&["<rom>", "<tileset>"])?;
+    let output = arguments.required_option("--output")?;
+    let sm = load(&positional[0])?;
+
+    let index = parse_number(&positional[1])?;
+    if index >= sm.tilesets.len() {
+        return Err(UsageError(format!("There is no Tileset {}.", index)).into());
+    }
+
+    let (palette, graphics, tile_table) = sm.get_tileset_data(index);
+    tileset_to_image(&tile_table, palette, &graphics)
+        .save(output)
+        .map_err(|error| format!("Failed to write {}: {}.", output, error))?;
+    Ok(())
+}
+
+/// Write every asset of the ROM into the project folder.
+pub fn export(arguments: &Arguments) -> CommandResult {
+    arguments.expect_flags(&[])?;
+    let positional = arguments.expect_positional(&["<rom>", "<project>"])?;
+    let sm = load(&positional[0])?;
+
+    project::export(&sm, Path::new(&positional[1]))?;
+    Ok(())
+}
+
+/// Rebuild the ROM with the assets of the project folder.
+pub fn build(arguments: &Arguments) -> CommandResult {
+    arguments.expect_flags(&[])?;
+    let positional = arguments.expect_positional(&["<rom>", "<project>"])?;
+    let output = arguments.required_option("--output")?;
+
+    let mut sm = project::build(read(&positional[0])?, Path::new(&positional[1]))?;
+    sm.save_to_file(output, true)
+        .map_err(|error| format!("Failed to write {}: {}.", output, error))?;
+    Ok(())
+}
+
+/// Apply a patch to the ROM, or create one from two ROMs.
+pub fn patch(arguments: &Arguments) -> CommandResult {
+    arguments.expect_flags(&["--create"])?;
+    let output = arguments.required_option("--output")?;
+
+    if arguments.flag("--create") {
+        let positional = arguments.expect_positional(&["<original>", "<modified>"])?;
+        let (_, original) = header::strip_copier_header(read(&positional[0])?);
+        let (_, modified) = header::strip_copier_header(read(&positional[1])?);
+
+        let patch = if output.to_lowercase().ends_with(".ips") {
+            ips::create(&original, &modified)
+        } else {
+            bps::create(&original, &modified)
+        };
+        write(output, &patch)
+    } else {
+        let positional = arguments.expect_positional(&["<rom>", "<patch>"])?;
+        let (copier_header, rom) = header::strip_copier_header(read(&positional[0])?);
+
+        let patched = patch::apply(&rom, &read(&positional[1])?)?;
+        write(
+            output,
+            &[copier_header.unwrap_or_default(), patched].concat(),
+        )
+    }
+}
+
+/// Check the ROM checksum and that all its data loads, failing if it doesn't.
+pub fn verify(arguments: &Arguments) -> CommandResult {
+    arguments.expect_flags(&[])?;
+    let sm = load(&arguments.expect_positional(&["<rom>"])?[0])?;
+
+    println!(
+        "{:?}{}",
+        sm.version,
+        if sm.version == RomVersion::JapanUsa {
+            " (vanilla)"
+        } else {
+            ""
+        }
+    );
+    for diagnostic in sm.diagnostics.iter() {
+        println!("Error: {}", diagnostic);
+    }
+
+    match sm.diagnostics.len() {
+        0 => {
+            println!("OK");
+            Ok(())
+        }
+        errors => Err(format!("Found {} errors.", errors).into()),
+    }
+}
@@ -0,0 +1,159 @@
+pub mod commands;
+
+use std::{error::Error, fmt};
+
+pub const USAGE: &str = "Usage: zen <command> [arguments]
+
+Commands:
+  info <rom>                                    Show the header and what was loaded from the ROM.
+  list-rooms <rom>                              List every Room with its area, size and States.
+  render-room <rom> <room> -o <png> [--state <n>] [--enemies] [--liquid]
+                                                Render the Room with its n-th State, the default one if not given,
+                                                drawing its enemies with '--enemies' and its liquid with '--liquid'.
+  render-tileset <rom> <tileset> -o <png>       Render the Tileset with the given index.
+  export <rom> <project>                        Write every asset of the ROM into the project folder.
+  build <rom> <project> -o <rom>                Rebuild the ROM with the assets of the project folder.
+  patch <rom> <patch> -o <rom>                  Apply an IPS or BPS patch to the ROM.
+  patch --create <original> <modified> -o <patch>
+                                                Create a patch, in BPS format unless it ends with '.ips'.
+  verify <rom>                                  Check the ROM checksum and that all its data loads.
+
+Addresses are hexadecimal, as '$8F91F8' or '0x8F91F8'. Room addresses can leave out bank $8F.";
+
+/// The command line was not understood, so the usage should be shown.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageError(pub String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for UsageError {}
+
+/// Arguments after the command, split into positional ones, options with a value and flags.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Arguments {
+    pub positional: Vec<String>,
+    options: Vec<(String, String)>,
+    flags: Vec<String>,
+}
+
+/// Options which are followed by a value, along with their short form.
+const OPTIONS_WITH_VALUE: &[(&str, &str)] = &[("--output", "-o"), ("--state", "")];
+
+impl Arguments {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Arguments, UsageError> {
+        let mut arguments = Arguments::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            if let Some((long, _)) = OPTIONS_WITH_VALUE
+                .iter()
+                .find(|(long, short)| arg == *long || (!short.is_empty() && arg == *short))
+            {
+                let value = args
+                    .next()
+                    .ok_or_else(|| UsageError(format!("Missing value for '{}'.", long)))?;
+                arguments.options.push((long.to_string(), value));
+            } else if arg.starts_with("--") {
+                arguments.flags.push(arg);
+            } else {
+                arguments.positional.push(arg);
+            }
+        }
+
+        Ok(arguments)
+    }
+
+    /// The positional arguments, which must be exactly as many as 'names'.
+    pub fn expect_positional(&self, names: &[&str]) -> Result<&[String], UsageError> {
+        if self.positional.len() == names.len() {
+            Ok(&self.positional)
+        } else {
+            Err(UsageError(format!(
+                "Expected arguments: {}.",
+                names.join(" ")
+            )))
+        }
+    }
+
+    pub fn option(&self, long: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .find(|(option, _)| option == long)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn required_option(&self, long: &str) -> Result<&str, UsageError> {
+        self.option(long)
+            .ok_or_else(|| UsageError(format!("Missing option '{}'.", long)))
+    }
+
+    pub fn flag(&self, long: &str) -> bool {
+        self.flags.iter().any(|flag| flag == long)
+    }
+
+    /// Fail on flags which are not in 'known'.
+    pub fn expect_flags(&self, known: &[&str]) -> Result<(), UsageError> {
+        match self
+            .flags
+            .iter()
+            .find(|flag| !known.contains(&flag.as_str()))
+        {
+            Some(flag) => Err(UsageError(format!("Unknown option '{}'.", flag))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parse a hexadecimal '$' or '0x' prefixed number, or else a decimal one.
+pub fn parse_number(value: &str) -> Result<usize, UsageError> {
+    let number = match value.strip_prefix('$').or_else(|| value.strip_prefix("0x")) {
+        Some(hex) => usize::from_str_radix(hex, 16),
+        None => value.parse(),
+    };
+    number.map_err(|_| UsageError(format!("'{}' is not a number.", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    /// Split positional arguments, options with values and flags.
+    #[test]
+    fn parse_arguments() {
+        let arguments =
+            Arguments::parse(args("sm.smc $91F8 -o room.png --state 1 --create")).unwrap();
+
+        assert_eq!(
+            arguments.expect_positional(&["rom", "room"]).unwrap(),
+            ["sm.smc", "$91F8"]
+        );
+        assert!(arguments.expect_positional(&["rom"]).is_err());
+        assert_eq!(arguments.option("--output"), Some("room.png"));
+        assert_eq!(arguments.option("--state"), Some("1"));
+        assert!(arguments.flag("--create"));
+        assert!(arguments.expect_flags(&["--create"]).is_ok());
+        assert!(arguments.expect_flags(&[]).is_err());
+
+        assert_eq!(
+            Arguments::parse(args("sm.smc -o")),
+            Err(UsageError("Missing value for '--output'.".to_string()))
+        );
+    }
+
+    /// Parse hexadecimal and decimal numbers.
+    #[test]
+    fn parse_numbers() {
+        assert_eq!(parse_number("$8F91F8").unwrap(), 0x8F91F8);
+        assert_eq!(parse_number("0x91F8").unwrap(), 0x91F8);
+        assert_eq!(parse_number("12").unwrap(), 12);
+        assert!(parse_number("91F8").is_err());
+    }
+}
@@ -1,5 +1,33 @@
+use super::{Format, LZ5};
+
 /// Compress 'source' with Lz5 algorithm.
 pub fn compress(source: &[u8]) -> Vec<u8> {
+    compress_with_format(source, LZ5)
+}
+
+/// Compress 'source' with Lz5 algorithm, or any of its variants described by 'format'.
+pub fn compress_with_format(source: &[u8], format: Format) -> Vec<u8> {
+    let mut hash_chain = HashChain::new(source.len());
+    compress_with(source, format, |address| {
+        hash_chain.longest_match(source, address)
+    })
+}
+
+/// Compress 'source' with Lz5 algorithm, comparing every earlier position to find a match.
+/// It's the slow reference for 'compress', which produces the same output.
+pub fn compress_exhaustive(source: &[u8]) -> Vec<u8> {
+    compress_with(source, LZ5, |address| {
+        count_sliding_dictonary(&source[address..], &source[..address])
+    })
+}
+
+/// Compress 'source' taking the longest command at each address.
+/// 'find_match' gives the longest match for an address, and where its window starts.
+fn compress_with(
+    source: &[u8],
+    format: Format,
+    mut find_match: impl FnMut(usize) -> (usize, usize),
+) -> Vec<u8> {
     let mut output = Vec::new();
     let mut direct_copy_address = None;
 
@@ -11,8 +39,7 @@ pub fn compress(source: &[u8]) -> Vec<u8> {
         let byte_fill_size = count_byte_fill(&source[address..]);
         let word_fill_size = count_word_fill(&source[address..]);
         let incrementing_fill_size = count_incrementing_fill(&source[address..]);
-        let (sliding_size, window_address) =
-            count_sliding_dictonary(&source[address..], &source[..address]);
+        let (sliding_size, window_address) = find_match(address);
 
         for (compressed_size, compression_command) in [
             (byte_fill_size, 0x20),
@@ -48,7 +75,7 @@ pub fn compress(source: &[u8]) -> Vec<u8> {
                 0x20 => compress_byte_fill(&source[address..], bytes_compressed),
                 0x40 => compress_word_fill(&source[address..], bytes_compressed),
                 0x60 => compress_incrementing_fill(&source[address..], bytes_compressed),
-                _ => compress_offset_dictionary(address, window_address, sliding_size),
+                _ => compress_offset_dictionary(address, window_address, sliding_size, format),
             };
             output.extend(compressed_data);
             address += compressed_size;
@@ -82,6 +109,225 @@ pub fn compress(source: &[u8]) -> Vec<u8> {
     output
 }
 
+/// Number of bytes of a command is stored in 10 bits.
+const MAX_COMMAND_SIZE: usize = 0b11_1111_1111;
+/// Inverted sliding dictionary only exists as extended command,
+/// and a bigger size would make its first byte 0xFF, the end of data.
+const MAX_INVERTED_SLIDING_SIZE: usize = 0x300;
+
+/// Commands chosen by the optimal compression, with the data they need.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Command {
+    DirectCopy,
+    ByteFill,
+    WordFill,
+    IncrementingFill,
+    OffsetDictionary { window_address: usize, invert: bool },
+    SlidingDictionary { distance: usize, invert: bool },
+}
+
+impl Command {
+    fn code(&self) -> usize {
+        match self {
+            Command::DirectCopy => 0x00,
+            Command::ByteFill => 0x20,
+            Command::WordFill => 0x40,
+            Command::IncrementingFill => 0x60,
+            Command::OffsetDictionary { invert, .. } => 0x80 + 0x20 * (*invert as usize),
+            Command::SlidingDictionary { invert, .. } => 0xC0 + 0x20 * (*invert as usize),
+        }
+    }
+
+    /// Size of the whole command when compressing 'number_of_bytes'.
+    fn size(&self, number_of_bytes: usize) -> usize {
+        command_data_size(self.code(), number_of_bytes)
+            + match self {
+                Command::DirectCopy => number_of_bytes,
+                Command::WordFill | Command::OffsetDictionary { .. } => 2,
+                _ => 1,
+            }
+    }
+}
+
+/// Compress 'source' with Lz5 algorithm, choosing the commands which produce the smallest output.
+/// Unlike 'compress' it also uses the inverted dictionary commands, but it's much slower.
+pub fn compress_optimal(source: &[u8]) -> Vec<u8> {
+    compress_optimal_with_format(source, LZ5)
+}
+
+/// Same as 'compress_optimal', for any of the Lz5 variants described by 'format'.
+pub fn compress_optimal_with_format(source: &[u8], format: Format) -> Vec<u8> {
+    // 'sizes[address]' is the smallest output for 'source[..address]',
+    // and 'commands[address]' the last command used to reach it.
+    let mut sizes = vec![usize::MAX; source.len() + 1];
+    let mut commands: Vec<Option<(Command, usize)>> = vec![None; source.len() + 1];
+    sizes[0] = 0;
+    let mut hash_chain = HashChain::new(source.len());
+
+    for address in 0..=source.len() {
+        // Direct copies are the only commands which size depends on the data, so they end here.
+        for number_of_bytes in 1..=address.min(MAX_COMMAND_SIZE) {
+            let size = sizes[address - number_of_bytes] + Command::DirectCopy.size(number_of_bytes);
+            if size < sizes[address] {
+                sizes[address] = size;
+                commands[address] = Some((Command::DirectCopy, number_of_bytes));
+            }
+        }
+
+        if address == source.len() {
+            break;
+        }
+
+        for (command, max_number_of_bytes) in
+            find_commands(source, address, format, &mut hash_chain)
+        {
+            for number_of_bytes in 2..=max_number_of_bytes {
+                let size = sizes[address] + command.size(number_of_bytes);
+                if size < sizes[address + number_of_bytes] {
+                    sizes[address + number_of_bytes] = size;
+                    commands[address + number_of_bytes] = Some((command, number_of_bytes));
+                }
+            }
+        }
+    }
+
+    // Walk back from the end to find which commands were chosen.
+    let mut chosen_commands = Vec::new();
+    let mut address = source.len();
+    while let Some((command, number_of_bytes)) = commands[address] {
+        address -= number_of_bytes;
+        chosen_commands.push((address, command, number_of_bytes));
+    }
+
+    let mut output = Vec::with_capacity(sizes[source.len()] + 1);
+    for (address, command, number_of_bytes) in chosen_commands.into_iter().rev() {
+        let (mut compression, _) = create_command_data(command.code(), number_of_bytes);
+        match command {
+            Command::DirectCopy => compression.extend(&source[address..address + number_of_bytes]),
+            Command::ByteFill | Command::IncrementingFill => compression.push(source[address]),
+            Command::WordFill => compression.extend(&source[address..address + 2]),
+            Command::OffsetDictionary { window_address, .. } => {
+                compression.extend(format.offset_to_bytes(window_address as u16))
+            }
+            Command::SlidingDictionary { distance, .. } => compression.push(distance as u8),
+        }
+        output.extend(compression);
+    }
+
+    output.push(0xFF);
+    output
+}
+
+/// Find every command usable at 'address', with the most bytes each one can compress.
+/// Dictionary windows are only searched among the latest positions of 'hash_chain'.
+fn find_commands(
+    source: &[u8],
+    address: usize,
+    format: Format,
+    hash_chain: &mut HashChain,
+) -> Vec<(Command, usize)> {
+    let max_number_of_bytes = (source.len() - address).min(MAX_COMMAND_SIZE);
+    let source_to_compress = &source[address..address + max_number_of_bytes];
+
+    let mut commands = vec![
+        (
+            Command::ByteFill,
+            count_fill(source_to_compress, |i| {
+                source_to_compress[0] == source_to_compress[i]
+            }),
+        ),
+        (
+            Command::WordFill,
+            count_fill(source_to_compress, |i| {
+                source_to_compress[i % 2] == source_to_compress[i]
+            }),
+        ),
+        (
+            Command::IncrementingFill,
+            count_fill(source_to_compress, |i| {
+                source_to_compress[0].wrapping_add(i as u8) == source_to_compress[i]
+            }),
+        ),
+    ];
+
+    // For each kind of dictionary, keep the window with the longest match.
+    // Ties are taken by the earliest window, as positions come from the latest one.
+    hash_chain.add_positions(source, address);
+    let inverts: &[bool] = if format.extended_copies {
+        &[false, true]
+    } else {
+        &[false]
+    };
+    for &invert in inverts {
+        let mut offset = (0, 0);
+        let mut sliding = (0, 0);
+        if source_to_compress.len() >= 3 {
+            let start: Vec<u8> = source_to_compress[..3]
+                .iter()
+                .map(|byte| if invert { !byte } else { *byte })
+                .collect();
+            for window_address in hash_chain.positions(&start).take(MAX_CHAIN_DEPTH) {
+                let number_of_bytes =
+                    count_dictionary(source_to_compress, &source[window_address..address], invert);
+
+                if window_address <= 0xFFFF && number_of_bytes >= offset.1 {
+                    offset = (window_address, number_of_bytes);
+                }
+                if address - window_address <= 0xFF && number_of_bytes >= sliding.1 {
+                    sliding = (address - window_address, number_of_bytes);
+                }
+            }
+        }
+
+        commands.push((
+            Command::OffsetDictionary {
+                window_address: offset.0,
+                invert,
+            },
+            offset.1,
+        ));
+        if format.extended_copies {
+            commands.push((
+                Command::SlidingDictionary {
+                    distance: sliding.0,
+                    invert,
+                },
+                if invert {
+                    sliding.1.min(MAX_INVERTED_SLIDING_SIZE)
+                } else {
+                    sliding.1
+                },
+            ));
+        }
+    }
+
+    commands
+}
+
+/// Count how many bytes from the start of 'source' satisfy 'is_filled'.
+fn count_fill(source: &[u8], is_filled: impl Fn(usize) -> bool) -> usize {
+    (0..source.len()).take_while(|i| is_filled(*i)).count()
+}
+
+/// Count how many bytes from the start of 'source' are a repetition of 'window',
+/// with all its bits inverted if required.
+/// An inverted copy can't go past 'window', as the bytes it writes would be inverted again.
+fn count_dictionary(source: &[u8], window: &[u8], invert: bool) -> usize {
+    if invert {
+        source
+            .iter()
+            .zip(window)
+            .take_while(|(byte, window_byte)| **byte == !**window_byte)
+            .count()
+    } else {
+        source
+            .iter()
+            .zip(window.iter().cycle())
+            .take_while(|(byte, window_byte)| byte == window_byte)
+            .count()
+    }
+}
+
 /// Count first byte from 'source' as many times as possible.
 fn count_byte_fill(source: &[u8]) -> usize {
     let byte = source[0];
@@ -175,6 +421,100 @@ fn count_sliding_dictonary(source: &[u8], sliding_window: &[u8]) -> (usize, usiz
     }
 }
 
+/// Number of bits used to index the chains by the first three bytes of each position.
+const HASH_BITS: usize = 15;
+const NO_POSITION: usize = usize::MAX;
+/// Positions of a chain compared by the optimal compression, which looks for every command.
+const MAX_CHAIN_DEPTH: usize = 0x100;
+
+/// Chains of earlier positions from a source sharing the hash of their first three bytes.
+/// As a match needs at least three bytes, only positions in the same chain are compared.
+struct HashChain {
+    /// Most recent position for each hash.
+    heads: Vec<usize>,
+    /// Position before each one with the same hash.
+    previous: Vec<usize>,
+    /// Positions before this one are already in the chains.
+    number_of_positions: usize,
+}
+
+impl HashChain {
+    fn new(source_size: usize) -> Self {
+        HashChain {
+            heads: vec![NO_POSITION; 1 << HASH_BITS],
+            previous: vec![NO_POSITION; source_size],
+            number_of_positions: 0,
+        }
+    }
+
+    fn hash(bytes: &[u8]) -> usize {
+        let value = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]);
+        (value.wrapping_mul(0x9E37_79B1) >> (32 - HASH_BITS)) as usize
+    }
+
+    /// Add every position before 'address' which has three bytes to hash.
+    fn add_positions(&mut self, source: &[u8], address: usize) {
+        while self.number_of_positions < address {
+            let position = self.number_of_positions;
+            if position + 3 <= source.len() {
+                let hash = Self::hash(&source[position..]);
+                self.previous[position] = self.heads[hash];
+                self.heads[hash] = position;
+            }
+            self.number_of_positions += 1;
+        }
+    }
+
+    /// Positions added with the same hash as the first three of 'bytes', from the latest one.
+    fn positions(&self, bytes: &[u8]) -> impl Iterator<Item = usize> + '_ {
+        let head = Some(self.heads[Self::hash(bytes)]).filter(|head| *head != NO_POSITION);
+        std::iter::successors(head, move |position| {
+            Some(self.previous[*position]).filter(|previous| *previous != NO_POSITION)
+        })
+    }
+
+    /// Find the longest prefix of 'source[address..]' starting at an earlier position.
+    /// Same as 'count_sliding_dictonary', ties are taken by the latest position.
+    fn longest_match(&mut self, source: &[u8], address: usize) -> (usize, usize) {
+        self.add_positions(source, address);
+
+        let source_to_match = &source[address..];
+        if source_to_match.len() < 3 {
+            return (0, 0);
+        }
+
+        let mut number_of_bytes = 0;
+        let mut window_address = 0;
+
+        for position in self.positions(source_to_match) {
+            if number_of_bytes == source_to_match.len() {
+                break;
+            }
+            let window = &source[position..];
+
+            // A longer match must at least have the byte after the current longest one.
+            if window[number_of_bytes] == source_to_match[number_of_bytes] {
+                let bytes = window
+                    .iter()
+                    .zip(source_to_match)
+                    .take_while(|(window_byte, byte)| window_byte == byte)
+                    .count();
+                if number_of_bytes < bytes {
+                    number_of_bytes = bytes;
+                    window_address = position;
+                }
+            }
+        }
+
+        // It takes two bytes for the command + data. If it doesn't compress at least two, it's not worth it.
+        if number_of_bytes > 2 {
+            (number_of_bytes, window_address)
+        } else {
+            (0, 0)
+        }
+    }
+}
+
 /// Return 'bytes' from 'source'.
 fn compress_direct_copy(source: &[u8], number_of_bytes: usize) -> Vec<u8> {
     let (mut compression, bytes) = create_command_data(0x00, number_of_bytes);
@@ -214,26 +554,36 @@ fn compress_offset_dictionary(
     source_address: usize,
     window_offset: usize,
     window_size: usize,
+    format: Format,
 ) -> (Vec<u8>, usize) {
-    if (source_address - window_offset) <= 0xFF {
+    if format.extended_copies && (source_address - window_offset) <= 0xFF {
         let (mut compression, bytes) = create_command_data(0xC0, window_size);
         compression.push((source_address - window_offset) as u8);
         (compression, bytes)
     } else {
         let (mut compression, bytes) = create_command_data(0x80, window_size);
-        compression.push((window_offset & 0b0000_0000_1111_1111) as u8);
-        compression.push(((window_offset & 0b1111_1111_0000_0000) >> 8) as u8);
+        compression.extend(format.offset_to_bytes(window_offset as u16));
         (compression, bytes)
     }
 }
 
+/// Number of bytes used by 'command + number_of_bytes'.
+/// Inverted sliding dictionary (0xE0) shares its bits with the extended command, so it's always extended.
+fn command_data_size(command: usize, number_of_bytes: usize) -> usize {
+    if number_of_bytes - 1 > 0b0001_1111 || command == 0xE0 {
+        2
+    } else {
+        1
+    }
+}
+
 /// Return a one or two byte long 'command + number_of_bytes'.
 fn create_command_data(command: usize, number_of_bytes: usize) -> (Vec<u8>, usize) {
     let total_number_of_bytes = number_of_bytes.min(0b11_1111_1111);
     let number_of_bytes = total_number_of_bytes - 1; // One is add at decompression by default.
 
     // Extended command = 111C_CCBB BBBB_BBBB (Two Bytes)
-    if number_of_bytes > 0b0001_1111 {
+    if command_data_size(command, total_number_of_bytes) == 2 {
         (
             vec![
                 (0b1110_0000 + (command >> 3) + (number_of_bytes >> 8)) as u8,
@@ -1,17 +1,69 @@
-use super::Lz5Error;
+use super::{Format, Lz5Error, LZ5};
 
 /// Decompress 'source' with Lz5 algorithm.
 /// Reference: http://patrickjohnston.org/bank/80#fB0FF
 pub fn decompress(source: &[u8]) -> Result<Vec<u8>, Lz5Error> {
-    let mut source = source.into_iter();
-    let mut output = Vec::new();
+    Ok(decompress_with_size(source)?.0)
+}
+
+/// Number of bytes the compressed data at the start of 'source' occupies, including the end byte.
+pub fn compressed_size(source: &[u8]) -> Result<usize, Lz5Error> {
+    Ok(decompress_with_size(source)?.1)
+}
+
+/// Decompress 'source' with Lz5 algorithm,
+/// along with the number of bytes the compressed data occupies, including the end byte.
+pub fn decompress_with_size(source: &[u8]) -> Result<(Vec<u8>, usize), Lz5Error> {
+    decompress_with_format(source, LZ5)
+}
+
+/// Same as 'decompress_with_size', for any of the Lz5 variants described by 'format'.
+pub fn decompress_with_format(source: &[u8], format: Format) -> Result<(Vec<u8>, usize), Lz5Error> {
+    let mut decompressor = Decompressor::with_format(source, format);
+    while decompressor.next_command()?.is_some() {}
+
+    let compressed_size = decompressor.compressed_size();
+    Ok((decompressor.into_output(), compressed_size))
+}
+
+/// Decompress Lz5 data reading straight from 'source', one command at a time.
+/// Any data after the end byte is never read, so 'source' can be the rest of a ROM.
+pub struct Decompressor<'s> {
+    format: Format,
+    source: std::slice::Iter<'s, u8>,
+    source_size: usize,
+    output: Vec<u8>,
+    finished: bool,
+}
+
+impl<'s> Decompressor<'s> {
+    pub fn new(source: &'s [u8]) -> Self {
+        Self::with_format(source, LZ5)
+    }
 
-    loop {
-        let data = *source.next().ok_or(Lz5Error)?;
+    pub fn with_format(source: &'s [u8], format: Format) -> Self {
+        Decompressor {
+            format,
+            source: source.iter(),
+            source_size: source.len(),
+            output: Vec::new(),
+            finished: false,
+        }
+    }
+
+    /// Decompress the next command, returning the bytes it added to the output.
+    /// Returns 'None' once the end byte is reached.
+    pub fn next_command(&mut self) -> Result<Option<&[u8]>, Lz5Error> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let data = *self.source.next().ok_or(Lz5Error::Truncated)?;
 
         // End of file data = 0xFF
         if data == 0b1111_1111 {
-            break;
+            self.finished = true;
+            return Ok(None);
         }
 
         let (command, number_of_bytes) = if (data & 0b1110_0000) == 0b1110_0000 {
@@ -24,7 +76,7 @@ pub fn decompress(source: &[u8]) -> Result<Vec<u8>, Lz5Error> {
                 (data & 0b0001_1100) << 3,
                 // Number of Bytes:
                 ((data as usize & 0b0000_0011) << 8)
-                    + (*source.next().ok_or(Lz5Error)? as usize)
+                    + (*self.source.next().ok_or(Lz5Error::Truncated)? as usize)
                     + 1,
             )
         } else {
@@ -35,29 +87,55 @@ pub fn decompress(source: &[u8]) -> Result<Vec<u8>, Lz5Error> {
         };
 
         let decompressed_data = match command {
-            0x00 => decompress_direct_copy(&mut source, number_of_bytes),
-            0x20 => decompress_byte_fill(&mut source, number_of_bytes),
-            0x40 => decompress_word_fill(&mut source, number_of_bytes),
-            0x60 => decompress_incrementing_fill(&mut source, number_of_bytes)?,
-            0x80..=0xBF => decompress_offset_dictionary(
-                &mut source,
-                &output,
+            0x00 => decompress_direct_copy(&mut self.source, number_of_bytes),
+            0x20 => decompress_byte_fill(&mut self.source, number_of_bytes),
+            0x40 => decompress_word_fill(&mut self.source, number_of_bytes),
+            0x60 => decompress_incrementing_fill(&mut self.source, number_of_bytes)?,
+            0x80 => decompress_offset_dictionary(
+                &mut self.source,
+                &self.output,
                 number_of_bytes,
-                (command & 0b0010_0000) == 0b0010_0000,
+                false,
+                self.format,
             )?,
-            0xC0..=0xE0 => decompress_sliding_dictionary(
-                &mut source,
-                &output,
+            0xA0 if self.format.extended_copies => decompress_offset_dictionary(
+                &mut self.source,
+                &self.output,
+                number_of_bytes,
+                true,
+                self.format,
+            )?,
+            0xC0..=0xE0 if self.format.extended_copies => decompress_sliding_dictionary(
+                &mut self.source,
+                &self.output,
                 number_of_bytes,
                 (command & 0b0010_0000) == 0b0010_0000,
             )?,
-            _ => return Err(Lz5Error), // Can't happen. All possible values for 0bXXX0_0000 covered.
+            _ => return Err(Lz5Error::BadCommand), // Only dictionary commands missing from 'format'.
         };
 
-        output.extend(decompressed_data);
+        let start = self.output.len();
+        self.output.extend(decompressed_data);
+        Ok(Some(&self.output[start..]))
+    }
+
+    /// Number of bytes read from 'source' so far.
+    pub fn compressed_size(&self) -> usize {
+        self.source_size - self.source.as_slice().len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
     }
 
-    Ok(output)
+    /// Data decompressed so far.
+    pub fn output(&self) -> &[u8] {
+        &self.output
+    }
+
+    pub fn into_output(self) -> Vec<u8> {
+        self.output
+    }
 }
 
 /// Copy 'number_of_bytes' from 'source' as is. Basically these bytes were not compressed.
@@ -101,7 +179,7 @@ fn decompress_incrementing_fill<'s>(
     source: &mut impl Iterator<Item = &'s u8>,
     number_of_bytes: usize,
 ) -> Result<Vec<u8>, Lz5Error> {
-    let data = *source.next().ok_or(Lz5Error)?;
+    let data = *source.next().ok_or(Lz5Error::Truncated)?;
     Ok((0..=number_of_bytes - 1)
         .map(|value| value.wrapping_add(data as usize) as u8)
         .collect())
@@ -113,16 +191,17 @@ fn decompress_offset_dictionary<'s>(
     output: &Vec<u8>,
     number_of_bytes: usize,
     invert: bool,
+    format: Format,
 ) -> Result<Vec<u8>, Lz5Error> {
-    let offset = u16::from_le_bytes([
-        *source.next().ok_or(Lz5Error)?,
-        *source.next().ok_or(Lz5Error)?,
+    let offset = format.offset_from_bytes([
+        *source.next().ok_or(Lz5Error::Truncated)?,
+        *source.next().ok_or(Lz5Error::Truncated)?,
     ]) as usize;
 
     if offset <= output.len() {
         Ok(copy_dictionary(&output[offset..], number_of_bytes, invert))
     } else {
-        Err(Lz5Error)
+        Err(Lz5Error::BadCommand)
     }
 }
 
@@ -135,23 +214,24 @@ fn decompress_sliding_dictionary<'s>(
 ) -> Result<Vec<u8>, Lz5Error> {
     let offset = output
         .len()
-        .checked_sub(*source.next().ok_or(Lz5Error)? as usize)
-        .ok_or(Lz5Error)?;
+        .checked_sub(*source.next().ok_or(Lz5Error::Truncated)? as usize)
+        .ok_or(Lz5Error::BadCommand)?;
     Ok(copy_dictionary(&output[offset..], number_of_bytes, invert))
 }
 
 /// Copy a 'number_of_bytes' from 'offseted_output', inverting all bits if required.
+/// Bytes are copied one at a time as the game does, so a copy longer than 'offseted_output'
+/// repeats the bytes it just wrote, and an inverted one inverts them again.
 fn copy_dictionary(offseted_output: &[u8], number_of_bytes: usize, invert: bool) -> Vec<u8> {
-    let decompressed_data = offseted_output
-        .into_iter()
-        .cycle()
-        .take(number_of_bytes)
-        .copied();
-    if invert {
-        decompressed_data.map(|value| !value).collect()
-    } else {
-        decompressed_data.collect()
+    let mut window = offseted_output.to_vec();
+    for index in 0..number_of_bytes {
+        match window.get(index) {
+            Some(value) if invert => window.push(!value),
+            Some(value) => window.push(*value),
+            None => break,
+        }
     }
+    window.split_off(offseted_output.len())
 }
 
 #[cfg(test)]
@@ -183,6 +263,73 @@ mod tests {
         }
     }
 
+    /// Measure the compressed size, ignoring any data after the end byte.
+    #[test]
+    fn measure_compressed_size() {
+        #[rustfmt::skip]
+        let data = [
+            0x02, 0x01, 0x02, 0x03, // Direct copy 3 bytes.
+            0x23, 0xAA,             // Byte fill 4 bytes.
+            0xFF,                   // End of data.
+            0x12, 0x34,             // Unrelated data.
+        ];
+
+        assert_eq!(compressed_size(&data).unwrap(), 7);
+        assert_eq!(
+            decompress(&data).unwrap(),
+            [0x01, 0x02, 0x03, 0xAA, 0xAA, 0xAA, 0xAA]
+        );
+        assert!(compressed_size(&data[..6]).is_err());
+    }
+
+    /// Decompress one command at a time, straight from a bigger slice.
+    #[test]
+    fn decompress_one_command_at_a_time() {
+        #[rustfmt::skip]
+        let rom = [
+            0x01, 0x12, 0x34, // Direct copy 2 bytes.
+            0xC1, 0x02,       // Sliding dictionary 2 bytes, 2 bytes behind.
+            0xFF,             // End of data.
+            0x56, 0x78,       // Unrelated data.
+        ];
+
+        let mut decompressor = Decompressor::new(&rom);
+        assert_eq!(
+            decompressor.next_command().unwrap(),
+            Some(&[0x12, 0x34][..])
+        );
+        assert_eq!(decompressor.compressed_size(), 3);
+        assert_eq!(
+            decompressor.next_command().unwrap(),
+            Some(&[0x12, 0x34][..])
+        );
+        assert!(!decompressor.is_finished());
+        assert_eq!(decompressor.next_command().unwrap(), None);
+        assert!(decompressor.is_finished());
+        assert_eq!(decompressor.output(), [0x12, 0x34, 0x12, 0x34]);
+
+        assert_eq!(
+            decompress_with_size(&rom).unwrap(),
+            (vec![0x12, 0x34, 0x12, 0x34], 6)
+        );
+    }
+
+    /// Decompress dictionary copies overlapping what they write, one byte at a time.
+    #[test]
+    fn decompress_overlapping_dictionary_copies() {
+        #[rustfmt::skip]
+        let data = [
+            0x01, 0x12, 0x34,       // Direct copy 2 bytes.
+            0x83, 0x00, 0x00,       // Offset dictionary 4 bytes, from the start.
+            0xFC, 0x03, 0x02,       // Inverted sliding dictionary 4 bytes, 2 bytes behind.
+            0xFF,                   // End of data.
+        ];
+        assert_eq!(
+            decompress(&data).unwrap(),
+            [0x12, 0x34, 0x12, 0x34, 0x12, 0x34, 0xED, 0xCB, 0x12, 0x34]
+        );
+    }
+
     /// Decompress a offset dictionary command,
     /// but with an offset exceeding the current decompressed data size.
     #[test]
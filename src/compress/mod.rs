@@ -2,23 +2,121 @@ pub mod lz5_compress;
 pub mod lz5_decompress;
 
 pub use lz5_compress::compress as lz5_compress;
+pub use lz5_compress::compress_exhaustive as lz5_compress_exhaustive;
+pub use lz5_compress::compress_optimal as lz5_compress_optimal;
+pub use lz5_decompress::compressed_size as lz5_compressed_size;
 pub use lz5_decompress::decompress as lz5_decompress;
+pub use lz5_decompress::decompress_with_size as lz5_decompress_with_size;
+pub use lz5_decompress::Decompressor as Lz5Decompressor;
 
 use std::{error::Error, fmt};
 
-pub struct Lz5Error;
+/// Why data could not be decompressed using LZ5 algorithm, or any of its variants.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Lz5Error {
+    /// The data ended before the end byte.
+    Truncated,
+    /// A command is invalid, like copying from outside of the output.
+    BadCommand,
+}
 
 impl Error for Lz5Error {}
 
 impl fmt::Display for Lz5Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Failed to decompress data using LZ5 algorithm.")
+        match self {
+            Lz5Error::Truncated => write!(f, "LZ5 data is truncated"),
+            Lz5Error::BadCommand => write!(f, "bad LZ5 command"),
+        }
+    }
+}
+
+/// Differences between Lz5 and the other Nintendo formats, as they all share the same commands.
+/// Reference: Lunar Compress documentation, formats LC_LZ1, LC_LZ2 and LC_LZ5.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Format {
+    /// Offset of dictionary copies is stored with the high byte first.
+    pub big_endian_offset: bool,
+    /// Supports the inverted (0xA0, 0xE0) and sliding (0xC0) dictionary commands.
+    pub extended_copies: bool,
+}
+
+impl Format {
+    fn offset_to_bytes(&self, offset: u16) -> [u8; 2] {
+        if self.big_endian_offset {
+            offset.to_be_bytes()
+        } else {
+            offset.to_le_bytes()
+        }
+    }
+
+    fn offset_from_bytes(&self, bytes: [u8; 2]) -> u16 {
+        if self.big_endian_offset {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        }
+    }
+}
+
+/// Super Metroid format.
+pub const LZ5: Format = Format {
+    big_endian_offset: false,
+    extended_copies: true,
+};
+/// Format with the dictionary offset byte swapped, and without the extended copies.
+pub const LZ2: Format = Format {
+    big_endian_offset: true,
+    extended_copies: false,
+};
+/// Format with only the plain commands of Lz5.
+pub const LZ1: Format = Format {
+    big_endian_offset: false,
+    extended_copies: false,
+};
+
+/// A compression format, so each asset can be handled with the one it was made with.
+pub trait Compression {
+    fn compress(&self, source: &[u8]) -> Vec<u8>;
+    fn decompress(&self, source: &[u8]) -> Result<Vec<u8>, Lz5Error>;
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Lz5;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Lz2;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Lz1;
+
+impl Compression for Lz5 {
+    fn compress(&self, source: &[u8]) -> Vec<u8> {
+        lz5_compress(source)
+    }
+
+    fn decompress(&self, source: &[u8]) -> Result<Vec<u8>, Lz5Error> {
+        lz5_decompress(source)
     }
 }
 
-impl fmt::Debug for Lz5Error {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Failed to decompress data using LZ5 algorithm.")
+impl Compression for Lz2 {
+    fn compress(&self, source: &[u8]) -> Vec<u8> {
+        lz5_compress::compress_with_format(source, LZ2)
+    }
+
+    fn decompress(&self, source: &[u8]) -> Result<Vec<u8>, Lz5Error> {
+        Ok(lz5_decompress::decompress_with_format(source, LZ2)?.0)
+    }
+}
+
+impl Compression for Lz1 {
+    fn compress(&self, source: &[u8]) -> Vec<u8> {
+        lz5_compress::compress_with_format(source, LZ1)
+    }
+
+    fn decompress(&self, source: &[u8]) -> Result<Vec<u8>, Lz5Error> {
+        Ok(lz5_decompress::decompress_with_format(source, LZ1)?.0)
     }
 }
 
@@ -50,4 +148,110 @@ mod tests {
             assert_eq!(decompressed_data, data);
         }
     }
+
+    /// Compression with hash chains gives the same output as comparing every earlier position.
+    #[test]
+    fn compress_same_as_exhaustive_lz5() {
+        let test_cases: [Vec<u8>; 4] = [
+            vec![0x42, 0x42],
+            [
+                vec![0x00; 0x500],
+                vec![0x12, 0x34].repeat(0x300),
+                vec![0x00; 0x500],
+            ]
+            .concat(),
+            (0..0x2000_u32).map(|i| (i * i % 0x3D) as u8).collect(),
+            (0..0x2000_u32)
+                .map(|i| (i / 0x20 * 7 + i % 3) as u8)
+                .collect(),
+        ];
+
+        for data in test_cases {
+            let compressed_data = super::lz5_compress(&data);
+            assert_eq!(compressed_data, super::lz5_compress_exhaustive(&data));
+            assert_eq!(super::lz5_decompress(&compressed_data).unwrap(), data);
+        }
+    }
+
+    /// Every format decompresses to the same data it compressed.
+    #[test]
+    fn compress_with_every_format() {
+        use super::{Compression, Lz1, Lz2, Lz5};
+
+        let data: Vec<u8> = (0..0x800_u32).map(|i| (i * i % 0x3D) as u8).collect();
+        let codecs: [&dyn Compression; 3] = [&Lz5, &Lz2, &Lz1];
+        for codec in codecs {
+            assert_eq!(codec.decompress(&codec.compress(&data)).unwrap(), data);
+        }
+    }
+
+    /// Lz2 stores dictionary offsets byte swapped, and has no sliding dictionary.
+    #[test]
+    fn decompress_lz2_dictionary() {
+        use super::{Compression, Lz1, Lz2};
+
+        #[rustfmt::skip]
+        let data = [
+            0x02, 0x12, 0x34, 0x56, // Direct copy 3 bytes.
+            0x81, 0x00, 0x01,       // Offset dictionary 2 bytes, from the second byte.
+            0xFF,                   // End of data.
+        ];
+        assert_eq!(
+            Lz2.decompress(&data).unwrap(),
+            [0x12, 0x34, 0x56, 0x34, 0x56]
+        );
+        assert!(Lz1.decompress(&data).is_err());
+        assert!(Lz2
+            .decompress(&[0x01, 0x12, 0x34, 0xC1, 0x02, 0xFF])
+            .is_err());
+    }
+
+    /// Optimal compression decompresses to the same data, and is never bigger than the greedy one.
+    #[test]
+    fn compress_with_optimal_lz5() {
+        let test_cases: [Vec<u8>; 5] = [
+            vec![],
+            vec![0x42],
+            (0..=0xFF).chain(0..=0xFF).collect(),
+            [vec![0x00; 0x50], vec![0x12, 0x34].repeat(0x50), vec![0x01]].concat(),
+            (0..0x400_u32).map(|i| (i * i % 0x3D) as u8).collect(),
+        ];
+
+        for data in test_cases {
+            let compressed_data = super::lz5_compress_optimal(&data);
+            assert_eq!(super::lz5_decompress(&compressed_data).unwrap(), data);
+            assert!(compressed_data.len() <= super::lz5_compress(&data).len());
+        }
+    }
+
+    /// Optimal compression doesn't use inverted copies overlapping what they write,
+    /// as the game copies one byte at a time and would invert those bytes again.
+    #[test]
+    fn compress_repeated_inverted_data_with_optimal_lz5() {
+        let data: Vec<u8> = vec![0x13, 0x57, 0x9B, 0xDF, 0x02, 0x46, 0x8A, 0xCE];
+        let inverted_data: Vec<u8> = data.iter().map(|byte| !byte).collect();
+        let data = [data, inverted_data.repeat(4)].concat();
+
+        let compressed_data = super::lz5_compress_optimal(&data);
+        assert_ne!(compressed_data[9..], [0xBF, 0x00, 0x00, 0xFF]);
+        assert_eq!(super::lz5_decompress(&compressed_data).unwrap(), data);
+    }
+
+    /// Optimal compression uses inverted dictionary commands.
+    #[test]
+    fn compress_inverted_data_with_optimal_lz5() {
+        let data: Vec<u8> = [0x13, 0x57, 0x9B, 0xDF, 0x02, 0x46, 0x8A, 0xCE].repeat(3);
+        let inverted_data: Vec<u8> = data.iter().map(|byte| !byte).collect();
+
+        let compressed_data = super::lz5_compress_optimal(&[data, inverted_data].concat());
+        assert_eq!(
+            compressed_data,
+            [
+                0x07, 0x13, 0x57, 0x9B, 0xDF, 0x02, 0x46, 0x8A, 0xCE, // Direct copy 8 bytes.
+                0xCF, 0x08, // Sliding dictionary 16 bytes, 8 bytes behind.
+                0xB7, 0x00, 0x00, // Inverted offset dictionary 24 bytes, from the start.
+                0xFF, // End of data.
+            ]
+        );
+    }
 }
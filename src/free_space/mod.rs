@@ -0,0 +1,240 @@
+use std::{error::Error, fmt, ops::Range};
+
+use crate::address::Pc;
+
+/// Each LoRom bank maps $8000 bytes of ROM.
+pub const BANK_SIZE: usize = 0x8000;
+
+/// Regions of the ROM available for writing, in 'Pc' addresses.
+/// Allocations never cross a bank boundary, as most routines can't read across them.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FreeSpace {
+    regions: Vec<Range<usize>>,
+}
+
+impl FreeSpace {
+    pub fn regions(&self) -> &[Range<usize>] {
+        &self.regions
+    }
+
+    pub fn total(&self) -> usize {
+        self.regions.iter().map(|region| region.len()).sum()
+    }
+
+    /// Mark 'size' bytes from 'start' as free, merging with neighbour regions.
+    pub fn free(&mut self, start: Pc, size: usize) {
+        if size == 0 {
+            return;
+        }
+
+        self.regions.push(start.address..start.address + size);
+        self.regions.sort_by_key(|region| region.start);
+
+        let mut merged: Vec<Range<usize>> = Vec::with_capacity(self.regions.len());
+        for region in self.regions.drain(..) {
+            match merged.last_mut() {
+                Some(last) if region.start <= last.end => last.end = last.end.max(region.end),
+                _ => merged.push(region),
+            }
+        }
+        self.regions = merged;
+    }
+
+    /// Mark 'size' bytes from 'start' as used, whether or not they were free.
+    pub fn reserve(&mut self, start: Pc, size: usize) {
+        let used = start.address..start.address + size;
+
+        self.regions = self
+            .regions
+            .drain(..)
+            .flat_map(|region| {
+                [
+                    region.start..region.end.min(used.start),
+                    region.start.max(used.end)..region.end,
+                ]
+            })
+            .filter(|region| !region.is_empty())
+            .collect();
+    }
+
+    /// Allocate 'size' bytes anywhere in free space.
+    pub fn allocate(&mut self, size: usize) -> Result<Pc, FreeSpaceError> {
+        self.allocate_within(size, 0..usize::MAX)
+    }
+
+    /// Allocate 'size' bytes anywhere in free space but inside the 'Pc' ranges of 'excluded'.
+    pub fn allocate_outside(
+        &mut self,
+        size: usize,
+        excluded: &[Range<usize>],
+    ) -> Result<Pc, FreeSpaceError> {
+        let mut excluded = excluded.to_vec();
+        excluded.sort_by_key(|range| range.start);
+        excluded.push(usize::MAX..usize::MAX);
+
+        let mut start = 0;
+        for range in excluded {
+            if start < range.start {
+                if let Ok(address) = self.allocate_within(size, start..range.start) {
+                    return Ok(address);
+                }
+            }
+            start = start.max(range.end);
+        }
+
+        Err(FreeSpaceError { size })
+    }
+
+    /// Allocate 'size' bytes inside the 'Pc' range 'area', using the first region it fits.
+    pub fn allocate_within(
+        &mut self,
+        size: usize,
+        area: Range<usize>,
+    ) -> Result<Pc, FreeSpaceError> {
+        for region in self.regions.iter() {
+            let mut start = region.start.max(area.start);
+            let end = region.end.min(area.end);
+
+            while start + size <= end {
+                let bank_end = (start / BANK_SIZE + 1) * BANK_SIZE;
+                if start + size <= bank_end {
+                    let address = Pc { address: start };
+                    self.reserve(address, size);
+                    return Ok(address);
+                }
+                start = bank_end;
+            }
+        }
+
+        Err(FreeSpaceError { size })
+    }
+}
+
+pub struct FreeSpaceError {
+    pub size: usize,
+}
+
+impl Error for FreeSpaceError {}
+
+impl fmt::Display for FreeSpaceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Not enough free space to allocate {} bytes.", self.size)
+    }
+}
+
+impl fmt::Debug for FreeSpaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Not enough free space to allocate {} bytes.", self.size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Free regions are merged with their neighbours and reserved ones are split.
+    #[test]
+    fn free_and_reserve_regions() {
+        let mut free_space = FreeSpace::default();
+        free_space.free(Pc { address: 0x100 }, 0x100);
+        free_space.free(Pc { address: 0x300 }, 0x100);
+        free_space.free(Pc { address: 0x200 }, 0x80);
+        assert_eq!(free_space.regions(), [0x100..0x280, 0x300..0x400]);
+
+        free_space.free(Pc { address: 0x280 }, 0x80);
+        assert_eq!(free_space.regions(), [0x100..0x400]);
+
+        free_space.reserve(Pc { address: 0x180 }, 0x100);
+        assert_eq!(free_space.regions(), [0x100..0x180, 0x280..0x400]);
+        assert_eq!(free_space.total(), 0x200);
+    }
+
+    /// Allocate from the first region with enough space, never crossing a bank.
+    #[test]
+    fn allocate_without_crossing_banks() {
+        let mut free_space = FreeSpace::default();
+        free_space.free(Pc { address: 0x10 }, 0x10);
+        free_space.free(
+            Pc {
+                address: BANK_SIZE - 0x20,
+            },
+            0x60,
+        );
+
+        assert_eq!(free_space.allocate(0x08).unwrap(), Pc { address: 0x10 });
+        assert_eq!(
+            free_space.allocate(0x30).unwrap(),
+            Pc { address: BANK_SIZE }
+        );
+        assert_eq!(
+            free_space.regions(),
+            [
+                0x18..0x20,
+                BANK_SIZE - 0x20..BANK_SIZE,
+                BANK_SIZE + 0x30..BANK_SIZE + 0x40
+            ]
+        );
+    }
+
+    /// Allocate only inside the requested area.
+    #[test]
+    fn allocate_within_area() {
+        let mut free_space = FreeSpace::default();
+        free_space.free(Pc { address: 0x00 }, 0x100);
+        free_space.free(
+            Pc {
+                address: BANK_SIZE * 2,
+            },
+            0x100,
+        );
+
+        assert_eq!(
+            free_space
+                .allocate_within(0x10, BANK_SIZE * 2..BANK_SIZE * 3)
+                .unwrap(),
+            Pc {
+                address: BANK_SIZE * 2
+            }
+        );
+        assert!(free_space
+            .allocate_within(0x100, BANK_SIZE * 2..BANK_SIZE * 3)
+            .is_err());
+    }
+
+    /// Allocate outside the excluded areas, even when the first free region is inside one.
+    #[test]
+    fn allocate_outside_areas() {
+        let mut free_space = FreeSpace::default();
+        free_space.free(Pc { address: 0x00 }, 0x100);
+        free_space.free(
+            Pc {
+                address: BANK_SIZE * 2,
+            },
+            0x100,
+        );
+        let excluded = [BANK_SIZE * 3..BANK_SIZE * 4, 0..BANK_SIZE];
+
+        assert_eq!(
+            free_space.allocate_outside(0x10, &excluded).unwrap(),
+            Pc {
+                address: BANK_SIZE * 2
+            }
+        );
+        assert!(free_space.allocate_outside(0x100, &excluded).is_err());
+        assert_eq!(
+            free_space.regions(),
+            [0x00..0x100, BANK_SIZE * 2 + 0x10..BANK_SIZE * 2 + 0x100]
+        );
+    }
+
+    /// Fail to allocate when there is not enough space left.
+    #[test]
+    fn allocate_without_enough_free_space() {
+        let mut free_space = FreeSpace::default();
+        free_space.free(Pc { address: 0x00 }, 0x10);
+
+        assert!(free_space.allocate(0x11).is_err());
+        assert!(free_space.allocate(0x10).is_ok());
+        assert!(free_space.allocate(0x01).is_err());
+    }
+}
@@ -4,12 +4,15 @@ pub const TILE_SIZE: usize = 8;
 pub const GFX_TILE_WIDTH: usize = 16;
 
 #[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Gfx {
     pub tiles: Vec<TileGfx>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TileGfx {
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex::tile_colors"))]
     pub colors: [u8; TILE_SIZE * TILE_SIZE],
 }
 
@@ -43,6 +46,21 @@ impl TileGfx {
         }
     }
 
+    /// Inverse of 'tile_4bpp', each row of colors is split back into its four bitplanes.
+    fn to_4bpp(self) -> [u8; TILE_SIZE * 4] {
+        let mut bytes = [0; TILE_SIZE * 4];
+        for (row, colors) in self.colors.chunks(TILE_SIZE).enumerate() {
+            for (x, color) in colors.iter().enumerate() {
+                let bit = TILE_SIZE - 1 - x;
+                bytes[row * 2] |= (color & 1) << bit;
+                bytes[row * 2 + 1] |= ((color >> 1) & 1) << bit;
+                bytes[TILE_SIZE * 2 + row * 2] |= ((color >> 2) & 1) << bit;
+                bytes[TILE_SIZE * 2 + row * 2 + 1] |= ((color >> 3) & 1) << bit;
+            }
+        }
+        bytes
+    }
+
     pub fn flip(&self, flip: (bool, bool)) -> [u8; TILE_SIZE * TILE_SIZE] {
         let mut colors = [0; TILE_SIZE * TILE_SIZE];
         for x in 0..TILE_SIZE {
@@ -67,7 +85,31 @@ pub fn from_4bpp(source: &[u8]) -> Gfx {
     }
 }
 
+/// Inverse of 'Gfx::to_indexed_colors', with rows of 'GFX_TILE_WIDTH' tiles.
+pub fn from_indexed_colors(colors: &[u8]) -> Gfx {
+    let row_width = GFX_TILE_WIDTH * TILE_SIZE;
+    let mut tiles = Vec::with_capacity(colors.len() / (TILE_SIZE * TILE_SIZE));
+
+    for row_of_tiles in colors.chunks(row_width * TILE_SIZE) {
+        for tile_number in 0..GFX_TILE_WIDTH {
+            let mut tile = TileGfx {
+                colors: [0; TILE_SIZE * TILE_SIZE],
+            };
+            for (tile_row, colors_row) in tile.colors.chunks_mut(TILE_SIZE).enumerate() {
+                let start = tile_row * row_width + tile_number * TILE_SIZE;
+                colors_row.copy_from_slice(&row_of_tiles[start..start + TILE_SIZE]);
+            }
+            tiles.push(tile);
+        }
+    }
+    Gfx { tiles }
+}
+
 impl Gfx {
+    pub fn to_4bpp(&self) -> Vec<u8> {
+        self.tiles.iter().flat_map(|tile| tile.to_4bpp()).collect()
+    }
+
     pub fn to_indexed_colors(&self) -> Vec<u8> {
         let mut gfx_index_colors = Vec::with_capacity(GFX_TILE_WIDTH * TILE_SIZE);
         // Loop each Tile row
@@ -191,6 +233,7 @@ mod tests {
     }
 
     /// Load a Gfx composed of many TileGfx in 4bpp format.
+    /// Convert it back into 4bpp.
     #[test]
     fn load_gfx_from_4bpp() {
         #[rustfmt::skip]
@@ -256,9 +299,10 @@ mod tests {
         ];
 
         assert_eq!(from_4bpp(&gfx_in_4bpp), expected_gfx);
+        assert_eq!(expected_gfx.to_4bpp(), gfx_in_4bpp);
     }
 
-    /// Convert a Gfx into a vector with all indexed colors, row by row.
+    /// Convert a Gfx into a vector with all indexed colors, row by row, and back.
     #[test]
     fn convert_gfx_to_indexed_colors() {
         #[rustfmt::skip]
@@ -351,5 +395,6 @@ mod tests {
         };
 
         assert_eq!(gfx.to_indexed_colors(), expected_indexed_colors);
+        assert_eq!(from_indexed_colors(&expected_indexed_colors), gfx);
     }
 }
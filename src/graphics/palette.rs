@@ -5,13 +5,14 @@ use crate::ParseError;
 pub const NUMBER_OF_SUB_PALETTES: usize = 8;
 
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Palette {
     pub sub_palettes: [SubPalette; NUMBER_OF_SUB_PALETTES],
 }
 
 /// Palette format reference: https://georgjz.github.io/snesaa03/
 pub fn from_bytes(mut source: &[u8]) -> Result<Palette, ParseError> {
-    let bytes_per_color = if source[..3] == *b"TPL" {
+    let bytes_per_color = if source.get(..3) == Some(&b"TPL"[..]) {
         // If bytes contain 'TPL' header, extract type.
         let tpl_type = source[3];
         source = &source[4..];
@@ -19,7 +20,7 @@ pub fn from_bytes(mut source: &[u8]) -> Result<Palette, ParseError> {
         match tpl_type {
             0x00 => 3,
             0x02 => 2,
-            _ => return Err(ParseError),
+            _ => return Err(ParseError::BadHeader),
         }
     } else {
         2 // SNES Default.
@@ -54,7 +55,7 @@ pub fn from_bytes(mut source: &[u8]) -> Result<Palette, ParseError> {
         })
     } else {
         // Palette need exact NUMBER_OF_SUB_PALETTES subpalettes.
-        Err(ParseError)
+        Err(ParseError::InvalidSize)
     }
 }
 
@@ -88,16 +89,23 @@ impl Palette {
         }
         bytes
     }
+
+    /// Palette with the 'TPL' header of two bytes colors, as used by Tile Layer Pro.
+    pub fn to_tpl(&self) -> Vec<u8> {
+        [&b"TPL\x02"[..], &self.to_bytes()].concat()
+    }
 }
 
 pub const COLORS_BY_SUB_PALETTE: usize = 16;
 
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SubPalette {
     pub colors: [Bgr555; COLORS_BY_SUB_PALETTE],
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Bgr555 {
     pub r: u8,
     pub g: u8,
@@ -124,7 +132,7 @@ impl Bgr555 {
                 g: source[1] >> 3 as u8,
                 b: source[2] >> 3 as u8,
             }),
-            _ => Err(ParseError),
+            _ => Err(ParseError::Truncated),
         }
     }
 
@@ -208,6 +216,7 @@ mod tests {
         let palette = from_bytes(&palette_bytes_with_header)?;
         assert_eq!(palette, expected_palette);
         assert_eq!(palette.to_bytes(), palette_bytes);
+        assert_eq!(palette.to_tpl(), palette_bytes_with_header);
 
         Ok(())
     }
@@ -0,0 +1,286 @@
+use crate::{address::Mapping, ParseError};
+
+/// Position of the internal header in an unheadered LoRom image ($00:FFC0).
+pub const LO_ROM_HEADER: usize = 0x7FC0;
+/// Position of the internal header in ExLoRom images ($00:FFC0), past the first 4 MB.
+pub const EX_LO_ROM_HEADER: usize = 0x40_7FC0;
+/// Position of the internal header in HiRom ($C0:FFC0) and ExHiRom ($40:FFC0) images.
+pub const HI_ROM_HEADER: usize = 0xFFC0;
+pub const EX_HI_ROM_HEADER: usize = 0x40_FFC0;
+pub const HEADER_SIZE: usize = 0x20;
+pub const TITLE_SIZE: usize = 21;
+
+/// SMC/SWC copier header prepended to some ROM dumps.
+pub const COPIER_HEADER_SIZE: usize = 0x200;
+pub const ROM_BANK_SIZE: usize = 0x8000;
+
+/// Internal SNES header.
+/// Reference: https://snes.nesdev.org/wiki/ROM_header
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Header {
+    pub title: [u8; TITLE_SIZE],
+    pub map_mode: u8,
+    pub rom_type: u8,
+    pub rom_size: u8,
+    pub sram_size: u8,
+    pub destination: u8,
+    pub developer: u8,
+    pub version: u8,
+    pub checksum_complement: u16,
+    pub checksum: u16,
+}
+
+impl Header {
+    /// Title with the trailing padding removed.
+    pub fn title(&self) -> String {
+        String::from_utf8_lossy(&self.title)
+            .trim_end_matches([' ', '\0'])
+            .to_string()
+    }
+
+    /// LoRom is map mode $20, or $30 for the FastROM variant.
+    pub fn is_lo_rom(&self) -> bool {
+        self.map_mode & 0b1110_1111 == 0x20
+    }
+
+    /// Mapping given by the map mode. A LoRom bigger than 4 MB is an ExLoRom.
+    pub fn mapping(&self) -> Option<Mapping> {
+        match self.map_mode & 0b1110_1111 {
+            0x20 if self.rom_size > 0x0C => Some(Mapping::ExLoRom),
+            0x20 => Some(Mapping::LoRom),
+            0x22 => Some(Mapping::ExLoRom),
+            0x21 => Some(Mapping::HiRom),
+            0x25 => Some(Mapping::ExHiRom),
+            _ => None,
+        }
+    }
+
+    /// Destination codes from $02 to $0C are all PAL regions.
+    pub fn is_pal(&self) -> bool {
+        (0x02..=0x0C).contains(&self.destination)
+    }
+
+    /// Checksum and its complement must always add up to $FFFF.
+    pub fn has_valid_complement(&self) -> bool {
+        self.checksum ^ self.checksum_complement == 0xFFFF
+    }
+
+    pub fn to_bytes(&self) -> [u8; HEADER_SIZE] {
+        let mut output = [0; HEADER_SIZE];
+
+        output[..TITLE_SIZE].copy_from_slice(&self.title);
+        output[21] = self.map_mode;
+        output[22] = self.rom_type;
+        output[23] = self.rom_size;
+        output[24] = self.sram_size;
+        output[25] = self.destination;
+        output[26] = self.developer;
+        output[27] = self.version;
+        output[28..30].copy_from_slice(&self.checksum_complement.to_le_bytes());
+        output[30..32].copy_from_slice(&self.checksum.to_le_bytes());
+
+        output
+    }
+}
+
+#[rustfmt::skip]
+pub fn from_bytes(source: &[u8]) -> Result<Header, ParseError> {
+    if source.len() < HEADER_SIZE {
+        return Err(ParseError::Truncated);
+    }
+
+    let mut title = [0; TITLE_SIZE];
+    title.copy_from_slice(&source[..TITLE_SIZE]);
+
+    Ok(Header {
+        title,
+        map_mode:            source[21],
+        rom_type:            source[22],
+        rom_size:            source[23],
+        sram_size:           source[24],
+        destination:         source[25],
+        developer:           source[26],
+        version:             source[27],
+        checksum_complement: u16::from_le_bytes([source[28], source[29]]),
+        checksum:            u16::from_le_bytes([source[30], source[31]]),
+    })
+}
+
+/// Load the internal header of an unheadered LoRom image.
+pub fn from_lo_rom(rom: &[u8]) -> Result<Header, ParseError> {
+    from_bytes(rom.get(LO_ROM_HEADER..).ok_or(ParseError::Truncated)?)
+}
+
+/// Load the internal header of an unheadered image with any mapping.
+/// It's the first header found with a valid checksum complement, and a map mode matching where it was found.
+pub fn from_rom(rom: &[u8]) -> Result<Header, ParseError> {
+    [
+        (EX_HI_ROM_HEADER, Mapping::ExHiRom),
+        (EX_LO_ROM_HEADER, Mapping::ExLoRom),
+        (HI_ROM_HEADER, Mapping::HiRom),
+        (LO_ROM_HEADER, Mapping::LoRom),
+        (LO_ROM_HEADER, Mapping::ExLoRom),
+    ]
+    .iter()
+    .filter_map(|&(position, mapping)| {
+        let header = from_bytes(rom.get(position..)?).ok()?;
+        (header.has_valid_complement() && header.mapping() == Some(mapping)).then_some(header)
+    })
+    .next()
+    .ok_or(ParseError::BadHeader)
+}
+
+/// Size byte for a ROM of 'size' bytes, as 1 KB shifted left by its value.
+pub fn rom_size_byte(size: usize) -> u8 {
+    let kilobytes = (size / 0x400).max(1);
+    kilobytes.next_power_of_two().trailing_zeros() as u8
+}
+
+/// Sum of every byte of 'rom', as the internal checksum.
+/// A ROM which size is not a power of two is summed as if its last part was mirrored until it is.
+pub fn checksum(rom: &[u8]) -> u16 {
+    mirrored_sum(rom) as u16
+}
+
+fn mirrored_sum(data: &[u8]) -> u32 {
+    if data.is_empty() || data.len().is_power_of_two() {
+        return data
+            .iter()
+            .fold(0u32, |sum, byte| sum.wrapping_add(*byte as u32));
+    }
+
+    // The biggest power of two part is summed once, and the remaining part is repeated to fill the same size.
+    let size = 1 << (usize::BITS - 1 - data.len().leading_zeros());
+    let remaining = &data[size..];
+    let repetitions = size / remaining.len().next_power_of_two();
+
+    mirrored_sum(&data[..size])
+        .wrapping_add(mirrored_sum(remaining).wrapping_mul(repetitions as u32))
+}
+
+/// A copier header is detected when the file size is 512 bytes over a multiple of a bank.
+pub fn has_copier_header(data: &[u8]) -> bool {
+    data.len() % ROM_BANK_SIZE == COPIER_HEADER_SIZE
+}
+
+/// Split 'data' into its copier header, if any, and the unheadered ROM.
+pub fn strip_copier_header(mut data: Vec<u8>) -> (Option<Vec<u8>>, Vec<u8>) {
+    if has_copier_header(&data) {
+        let rom = data.split_off(COPIER_HEADER_SIZE);
+        (Some(data), rom)
+    } else {
+        (None, data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Load a header from bytes and convert it back into bytes.
+    #[test]
+    fn load_header_from_bytes() {
+        #[rustfmt::skip]
+        let data = [
+            b'S', b'U', b'P', b'E', b'R', b' ', b'M', b'E', b'T', b'R', b'O', b'I', b'D',
+            b' ', b' ', b' ', b' ', b' ', b' ', b' ', b' ', // Title
+            0x30,       // Map mode
+            0x02,       // Rom type
+            0x0C,       // Rom size
+            0x03,       // Sram size
+            0x00,       // Destination
+            0x01,       // Developer
+            0x00,       // Version
+            0x20, 0x07, // Checksum complement
+            0xDF, 0xF8, // Checksum
+        ];
+
+        let header = from_bytes(&data).unwrap();
+        assert_eq!(header.title(), "SUPER METROID");
+        assert_eq!(header.map_mode, 0x30);
+        assert_eq!(header.rom_size, 0x0C);
+        assert_eq!(header.checksum_complement, 0x0720);
+        assert_eq!(header.checksum, 0xF8DF);
+        assert!(header.is_lo_rom());
+        assert!(!header.is_pal());
+        assert!(header.has_valid_complement());
+
+        assert_eq!(header.to_bytes(), data);
+    }
+
+    /// Fail to load a header without enough bytes.
+    #[test]
+    fn load_header_with_incorrect_data_size() {
+        assert!(from_bytes(&[0; HEADER_SIZE - 1]).is_err());
+        assert!(from_lo_rom(&[0; LO_ROM_HEADER]).is_err());
+    }
+
+    /// Strip a copier header only when the size is 512 bytes over a bank multiple.
+    #[test]
+    fn strip_copier_header_from_rom() {
+        let headered = [
+            vec![0xAA; COPIER_HEADER_SIZE],
+            vec![0x55; ROM_BANK_SIZE * 2],
+        ]
+        .concat();
+        let (copier_header, rom) = strip_copier_header(headered);
+        assert_eq!(copier_header, Some(vec![0xAA; COPIER_HEADER_SIZE]));
+        assert_eq!(rom, vec![0x55; ROM_BANK_SIZE * 2]);
+
+        let (copier_header, rom) = strip_copier_header(vec![0x55; ROM_BANK_SIZE * 2]);
+        assert_eq!(copier_header, None);
+        assert_eq!(rom, vec![0x55; ROM_BANK_SIZE * 2]);
+    }
+
+    /// Find the header of a HiRom image, and pick its mapping from the map mode.
+    #[test]
+    fn load_header_from_hi_rom() {
+        let mut rom = vec![0; HI_ROM_HEADER + HEADER_SIZE];
+        rom[HI_ROM_HEADER + 21] = 0x31;
+        rom[HI_ROM_HEADER + 28..HI_ROM_HEADER + 32].copy_from_slice(&[0xFF, 0xFF, 0x00, 0x00]);
+
+        let header = from_rom(&rom).unwrap();
+        assert_eq!(header.mapping(), Some(Mapping::HiRom));
+        assert!(!header.is_lo_rom());
+
+        // A LoRom header at the same position is not taken.
+        rom[HI_ROM_HEADER + 21] = 0x30;
+        assert!(from_rom(&rom).is_err());
+
+        // An 8 MB LoRom is an ExLoRom.
+        rom.copy_within(HI_ROM_HEADER.., LO_ROM_HEADER);
+        rom[LO_ROM_HEADER + 23] = 0x0D;
+        assert_eq!(from_rom(&rom).unwrap().mapping(), Some(Mapping::ExLoRom));
+    }
+
+    /// Find the header of an ExLoRom image in bank $00, past the first 4 MB, with map mode $32.
+    #[test]
+    fn load_header_from_ex_lo_rom() {
+        let mut rom = vec![0; EX_LO_ROM_HEADER + HEADER_SIZE];
+        rom[EX_LO_ROM_HEADER + 21] = 0x32;
+        rom[EX_LO_ROM_HEADER + 28..EX_LO_ROM_HEADER + 32]
+            .copy_from_slice(&[0xFF, 0xFF, 0x00, 0x00]);
+
+        let header = from_rom(&rom).unwrap();
+        assert_eq!(header.map_mode, 0x32);
+        assert_eq!(header.mapping(), Some(Mapping::ExLoRom));
+    }
+
+    /// Sum a ROM with a size which is not a power of two by mirroring its last part.
+    #[test]
+    fn checksum_with_mirroring() {
+        assert_eq!(checksum(&[]), 0);
+        assert_eq!(checksum(&[0x01, 0x02, 0x03, 0x04]), 0x0A);
+
+        // 2 + 1, where the last part is summed twice.
+        let rom = [vec![0x01; 0x200], vec![0x02; 0x100]].concat();
+        assert_eq!(checksum(&rom), 0x200 + 0x02 * 0x100 * 2);
+
+        // 4 + 1 + 0.5, where the last 1.5 is mirrored like a ROM itself.
+        let rom = [vec![0x01; 0x400], vec![0x02; 0x100], vec![0x03; 0x80]].concat();
+        assert_eq!(checksum(&rom), 0x400 + (0x02 * 0x100 + 0x03 * 0x80 * 2) * 2);
+
+        // The sum wraps around.
+        assert_eq!(checksum(&vec![0xFF; 0x200]), (0xFF * 0x200) as u16);
+    }
+}
@@ -0,0 +1,153 @@
+//! Readable encodings used by the 'serde' feature.
+//! Addresses and pointers are written as "$8F91F8" strings, but plain numbers are also read.
+
+use std::{convert::TryFrom, fmt, marker::PhantomData};
+
+use serde::{
+    de::{self, Visitor},
+    Deserializer, Serializer,
+};
+
+/// Numbers written with enough hexadecimal digits for their size.
+pub trait HexNumber: Copy + TryFrom<u64> + Into<u64> {
+    const DIGITS: usize;
+}
+
+impl HexNumber for u8 {
+    const DIGITS: usize = 2;
+}
+
+impl HexNumber for u16 {
+    const DIGITS: usize = 4;
+}
+
+/// SNES addresses only use three bytes.
+impl HexNumber for u32 {
+    const DIGITS: usize = 6;
+}
+
+pub fn serialize<T: HexNumber, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("${:0width$X}", (*value).into(), width = T::DIGITS))
+}
+
+pub fn deserialize<'de, T: HexNumber, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    deserializer.deserialize_any(HexVisitor(PhantomData))
+}
+
+struct HexVisitor<T>(PhantomData<T>);
+
+impl<'de, T: HexNumber> Visitor<'de> for HexVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a number, or a hexadecimal string starting with '$'")
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<T, E> {
+        T::try_from(value).map_err(|_| E::invalid_value(de::Unexpected::Unsigned(value), &self))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<T, E> {
+        value
+            .strip_prefix('$')
+            .and_then(|hex| u64::from_str_radix(hex, 16).ok())
+            .and_then(|number| T::try_from(number).ok())
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Str(value), &self))
+    }
+}
+
+/// The 8x8 color indexes of a tile, as one row of hexadecimal digits per line.
+pub mod tile_colors {
+    use std::convert::TryInto;
+
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    use crate::graphics::gfx::TILE_SIZE;
+
+    pub fn serialize<S: Serializer>(
+        colors: &[u8; TILE_SIZE * TILE_SIZE],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(colors.chunks(TILE_SIZE).map(|row| {
+            row.iter()
+                .map(|color| format!("{:X}", color))
+                .collect::<String>()
+        }))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<[u8; TILE_SIZE * TILE_SIZE], D::Error> {
+        let rows = Vec::<String>::deserialize(deserializer)?;
+        let colors: Option<Vec<u8>> = rows
+            .iter()
+            .flat_map(|row| row.chars().map(|digit| digit.to_digit(16).map(|c| c as u8)))
+            .collect();
+
+        colors
+            .and_then(|colors| colors.try_into().ok())
+            .ok_or_else(|| de::Error::custom("expected 8 rows of 8 hexadecimal digits"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        graphics::gfx::TileGfx,
+        super_metroid::{
+            level_data::{Block, BlockType},
+            room::{Condition, Room, StateCondition},
+            state::State,
+        },
+    };
+
+    /// Serialize addresses as hexadecimal, and deserialize them back from strings or numbers.
+    #[test]
+    fn serialize_addresses_as_hexadecimal() {
+        let room = Room {
+            doors: 0x8916,
+            state_conditions: vec![StateCondition {
+                condition: Condition::DoorPointerIs(0x8916),
+                state_address: 0x91F8,
+            }],
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&room).unwrap();
+        assert!(json.contains(r#""doors":"$8916""#));
+        assert!(json.contains(r#""condition":{"DoorPointerIs":"$8916"},"state_address":"$91F8""#));
+        assert_eq!(serde_json::from_str::<Room>(&json).unwrap(), room);
+
+        let state = State {
+            level_address: 0xC2C2BB,
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&state).unwrap();
+        assert!(json.starts_with(r#"{"level_address":"$C2C2BB","tileset":0,"#));
+        assert_eq!(serde_json::from_str::<State>(&json).unwrap(), state);
+
+        let json = json.replace(r#""$C2C2BB""#, "12763835");
+        assert_eq!(serde_json::from_str::<State>(&json).unwrap(), state);
+        assert!(serde_json::from_str::<State>(&json.replace("12763835", r#""C2C2BB""#)).is_err());
+    }
+
+    /// Serialize Block types by their names, and tile colors as rows of hexadecimal digits.
+    #[test]
+    fn serialize_block_types_and_tile_colors() {
+        let block = Block {
+            block_type: BlockType::SolidBombable,
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&block).unwrap();
+        assert!(json.contains(r#""block_type":"SolidBombable""#));
+        assert_eq!(serde_json::from_str::<Block>(&json).unwrap(), block);
+
+        let mut colors = [0; 64];
+        colors[..8].copy_from_slice(&[0x0, 0x1, 0x2, 0x3, 0xC, 0xD, 0xE, 0xF]);
+        let tile = TileGfx { colors };
+        let json = serde_json::to_string(&tile).unwrap();
+        assert!(json.starts_with(r#"{"colors":["0123CDEF","00000000","#));
+        assert_eq!(serde_json::from_str::<TileGfx>(&json).unwrap(), tile);
+    }
+}
@@ -1,14 +1,19 @@
-use image::{Rgb, RgbImage};
+use std::collections::HashMap;
+
+use image::{GrayImage, Luma, Rgb, RgbImage};
 
 use crate::{
     graphics::{
-        gfx::{Gfx, TileGfx, GFX_TILE_WIDTH, TILE_SIZE},
+        gfx::{self, Gfx, TileGfx, GFX_TILE_WIDTH, TILE_SIZE},
         palette::{COLORS_BY_SUB_PALETTE, NUMBER_OF_SUB_PALETTES},
         Palette, Rgb888,
     },
     super_metroid::{
+        address::{ENEMY_POPULATIONS_BANK, ENEMY_SETS_BANK, FX_BANK},
+        fx::{self, Fx, FxType},
         level_data::{LevelData, BLOCKS_PER_SCREEN},
         room::Room,
+        spritemap::SpritemapEntry,
         state::State,
         tile_table::{TileTable, TILE_TABLE_SIZE},
         tileset::{tileset_to_colors, TILESET_BLOCK_SIZE},
@@ -74,6 +79,41 @@ impl Gfx {
         }
         img
     }
+
+    /// Gray image with one shade for each color index, which can be loaded back without a palette.
+    pub fn to_indexed_image(&self) -> GrayImage {
+        let [width, height] = self.size();
+        let mut img = GrayImage::new(width as u32, height as u32);
+        for (color_number, index_color) in self.to_indexed_colors().iter().enumerate() {
+            img.put_pixel(
+                (color_number % width) as u32,
+                (color_number / width) as u32,
+                Luma([index_color * INDEX_SHADE_STEP]),
+            );
+        }
+        img
+    }
+}
+
+/// Shades of gray between two color indexes, so the 16 indexes span from black to white.
+const INDEX_SHADE_STEP: u8 = 0x11;
+
+/// Inverse of 'Gfx::to_indexed_image'. Shades are rounded to their nearest index.
+/// Returns 'None' if the image isn't 'GFX_TILE_WIDTH' tiles wide or has a partial row of tiles.
+pub fn gfx_from_indexed_image(image: &GrayImage) -> Option<Gfx> {
+    if image.width() as usize != GFX_TILE_WIDTH * TILE_SIZE
+        || !(image.height() as usize).is_multiple_of(TILE_SIZE)
+    {
+        return None;
+    }
+
+    let colors: Vec<u8> = image
+        .pixels()
+        .map(|Luma([shade])| {
+            ((*shade as u16 + INDEX_SHADE_STEP as u16 / 2) / INDEX_SHADE_STEP as u16) as u8
+        })
+        .collect();
+    Some(gfx::from_indexed_colors(&colors))
 }
 
 pub fn tileset_to_image(tile_table: &TileTable, palette: &Palette, graphics: &Gfx) -> RgbImage {
@@ -126,4 +166,239 @@ impl SuperMetroid {
 
         level_data.to_image(room.size(), &tile_table, &palette, &graphics)
     }
+
+    /// Render the Room like 'room_to_image', with what 'options' asks drawn over it.
+    pub fn room_to_image_with(
+        &self,
+        room: &Room,
+        state: &State,
+        options: RoomImageOptions,
+    ) -> RgbImage {
+        let mut image = self.room_to_image(room, state);
+
+        if options.enemies {
+            self.draw_enemies(&mut image, state);
+        }
+        if options.liquid {
+            if let Some(fx) = self
+                .fx
+                .get(&(FX_BANK + state.fx as usize))
+                .and_then(|fx_list| fx::default_fx(fx_list))
+            {
+                draw_liquid(&mut image, fx);
+            }
+        }
+        image
+    }
+
+    /// Draw the enemies of the State with the first spritemap of their species, in the palette
+    /// slot their species has in the Enemy Set. Enemies whose species is missing from the
+    /// Enemy Set, or whose graphics or spritemap can't be read, are left out.
+    fn draw_enemies(&self, image: &mut RgbImage, state: &State) {
+        let (population, enemy_set) = match (
+            self.enemy_populations
+                .get(&(ENEMY_POPULATIONS_BANK + state.enemy_population as usize)),
+            self.enemy_sets
+                .get(&(ENEMY_SETS_BANK + state.enemy_set as usize)),
+        ) {
+            (Some(population), Some(enemy_set)) => (population, enemy_set),
+            _ => return,
+        };
+
+        // Each species of the Enemy Set has its palette loaded into its own sprite slot.
+        let mut palette = Palette::default();
+        let mut species = HashMap::new();
+        for entry in enemy_set.iter() {
+            if let (Some((graphics, species_palette)), Some(spritemap)) = (
+                self.species_graphics(entry.species),
+                self.species_spritemap(entry.species),
+            ) {
+                let slot = entry.palette as usize % NUMBER_OF_SUB_PALETTES;
+                palette.sub_palettes[slot] = species_palette.sub_palettes[0];
+                species.insert(entry.species, (graphics, spritemap, slot));
+            }
+        }
+
+        for enemy in population.enemies.iter() {
+            if let Some((graphics, spritemap, slot)) = species.get(&enemy.species) {
+                let position = (enemy.x as i64, enemy.y as i64);
+                draw_spritemap(image, position, spritemap, graphics, &palette, *slot);
+            }
+        }
+    }
+}
+
+/// What to draw over the level layers of a Room.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct RoomImageOptions {
+    /// Enemies of the State's Enemy Population.
+    pub enemies: bool,
+    /// Liquid of the State's FX for any Door, tinting everything below its surface.
+    pub liquid: bool,
+}
+
+/// Tint of each liquid, mixed half and half with the pixels below its surface.
+fn liquid_tint(fx_type: FxType) -> Option<Rgb<u8>> {
+    match fx_type {
+        FxType::Lava => Some(Rgb([0xF8, 0x48, 0x00])),
+        FxType::Acid => Some(Rgb([0x40, 0xE0, 0x00])),
+        FxType::Water => Some(Rgb([0x00, 0x60, 0xF8])),
+        _ => None,
+    }
+}
+
+/// Tint 'image' from the liquid surface at the base height of 'fx' down to its bottom.
+fn draw_liquid(image: &mut RgbImage, fx: &Fx) {
+    let tint = match liquid_tint(fx.fx_type) {
+        Some(tint) => tint,
+        None => return,
+    };
+
+    let surface = (fx.base_y as u32).min(image.height());
+    for y in surface..image.height() {
+        for x in 0..image.width() {
+            let pixel = image.get_pixel_mut(x, y);
+            for (channel, tint) in pixel.0.iter_mut().zip(tint.0.iter()) {
+                *channel = ((*channel as u16 + *tint as u16) / 2) as u8;
+            }
+        }
+    }
+}
+
+/// Draw the sprites of 'spritemap' around 'position', the first ones over the others.
+/// Their tiles are taken from 'graphics' as laid out in VRAM, and their sub palette is
+/// added to 'sub_palette'. Pixels outside of 'image' are skipped.
+fn draw_spritemap(
+    image: &mut RgbImage,
+    position: (i64, i64),
+    spritemap: &[SpritemapEntry],
+    graphics: &Gfx,
+    palette: &Palette,
+    sub_palette: usize,
+) {
+    for entry in spritemap.iter().rev() {
+        let size = if entry.big { TILE_SIZE * 2 } else { TILE_SIZE };
+        // Enemy graphics start at tile $100, in the second sprite name table.
+        let first_tile = entry.tile as usize & 0xFF;
+        let sub_palette = (sub_palette | entry.sub_palette as usize) % NUMBER_OF_SUB_PALETTES;
+
+        for y in 0..size {
+            for x in 0..size {
+                let source_x = if entry.x_flip { size - 1 - x } else { x };
+                let source_y = if entry.y_flip { size - 1 - y } else { y };
+                let tile =
+                    first_tile + (source_y / TILE_SIZE) * GFX_TILE_WIDTH + source_x / TILE_SIZE;
+                let idx_color = match graphics.tiles.get(tile) {
+                    Some(tile) => {
+                        tile.colors[(source_y % TILE_SIZE) * TILE_SIZE + source_x % TILE_SIZE]
+                    }
+                    None => continue,
+                };
+                let image_x = position.0 + entry.x as i64 + x as i64;
+                let image_y = position.1 + entry.y as i64 + y as i64;
+
+                // Index 0 is used for transparency.
+                if idx_color != 0
+                    && (0..image.width() as i64).contains(&image_x)
+                    && (0..image.height() as i64).contains(&image_y)
+                {
+                    let color: Rgb888 =
+                        palette.sub_palettes[sub_palette].colors[idx_color as usize].into();
+                    image.put_pixel(
+                        image_x as u32,
+                        image_y as u32,
+                        Rgb([color.r, color.g, color.b]),
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphics::palette::Bgr555;
+
+    /// Draw a spritemap with a small sprite over a flipped big one, skipping transparent
+    /// pixels and those off the image.
+    #[test]
+    fn draw_spritemap_of_enemy() {
+        let mut image = RgbImage::new(16, 16);
+        let mut palette = Palette::default();
+        palette.sub_palettes[1].colors[1] = Bgr555 {
+            r: 0x1F,
+            ..Default::default()
+        };
+        palette.sub_palettes[3].colors[1] = Bgr555 {
+            b: 0x1F,
+            ..Default::default()
+        };
+        let mut graphics = Gfx {
+            tiles: vec![TileGfx { colors: [0; 64] }; GFX_TILE_WIDTH * 2],
+        };
+        // The big sprite has two colored pixels, the second one under the small sprite.
+        graphics.tiles[0].colors[0] = 1;
+        graphics.tiles[1].colors[9] = 1;
+        graphics.tiles[2].colors = [1; 64];
+        let spritemap = [
+            SpritemapEntry {
+                x: -4,
+                y: -12,
+                tile: 0x102,
+                sub_palette: 2,
+                ..Default::default()
+            },
+            SpritemapEntry {
+                x: -8,
+                y: -8,
+                big: true,
+                tile: 0x100,
+                x_flip: true,
+                ..Default::default()
+            },
+        ];
+
+        draw_spritemap(&mut image, (4, 12), &spritemap, &graphics, &palette, 1);
+
+        let red = Rgb([0xF8, 0x00, 0x00]);
+        let blue = Rgb([0x00, 0x00, 0xF8]);
+        // The flipped big sprite spans from (-4, 4), so its colored pixel is at (11, 4).
+        assert_eq!(*image.get_pixel(11, 4), red);
+        assert_eq!(image.pixels().filter(|pixel| **pixel == red).count(), 1);
+        // The small sprite spans 8x8 pixels from (0, 0), over the big one.
+        assert_eq!(*image.get_pixel(0, 0), blue);
+        assert_eq!(*image.get_pixel(7, 7), blue);
+        assert_eq!(
+            image.pixels().filter(|pixel| **pixel == blue).count(),
+            8 * 8
+        );
+    }
+
+    /// Tint the pixels below the surface of a liquid, and nothing for other effects.
+    #[test]
+    fn draw_liquid_below_surface() {
+        let mut image = RgbImage::from_pixel(4, 4, Rgb([0x10, 0x20, 0x30]));
+        let mut fx = Fx {
+            base_y: 2,
+            fx_type: FxType::Fog,
+            ..Default::default()
+        };
+
+        draw_liquid(&mut image, &fx);
+        assert!(image
+            .pixels()
+            .all(|pixel| *pixel == Rgb([0x10, 0x20, 0x30])));
+
+        fx.fx_type = FxType::Water;
+        draw_liquid(&mut image, &fx);
+        assert_eq!(*image.get_pixel(3, 1), Rgb([0x10, 0x20, 0x30]));
+        assert_eq!(*image.get_pixel(0, 2), Rgb([0x08, 0x40, 0x94]));
+        assert_eq!(*image.get_pixel(3, 3), Rgb([0x08, 0x40, 0x94]));
+
+        // A surface below the Room tints nothing.
+        fx.base_y = 0xFFFF;
+        draw_liquid(&mut image, &fx);
+        assert_eq!(*image.get_pixel(0, 0), Rgb([0x10, 0x20, 0x30]));
+    }
 }
@@ -1,21 +1,169 @@
 pub mod address;
 pub mod compress;
+pub mod free_space;
 pub mod graphics;
+pub mod header;
+#[cfg(feature = "serde")]
+mod hex;
 pub mod image;
+pub mod patch;
+pub mod project;
 pub mod super_metroid;
 
-pub struct ParseError;
+use std::fmt;
 
-impl std::fmt::Display for ParseError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Failed to parse bytes into data.")
-    }
+use address::Mapping;
+use compress::Lz5Error;
+use patch::PatchError;
+
+/// Why bytes could not be parsed into data.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParseError {
+    /// There are less bytes than the data needs.
+    Truncated,
+    /// A header or magic value has an unexpected value.
+    BadHeader,
+    /// The data has more elements than allowed.
+    InvalidSize,
 }
 
-impl std::fmt::Debug for ParseError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Failed to parse bytes into data.")
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Truncated => write!(f, "data is truncated"),
+            ParseError::BadHeader => write!(f, "bad header"),
+            ParseError::InvalidSize => write!(f, "invalid size"),
+        }
     }
 }
 
 impl std::error::Error for ParseError {}
+
+/// Structures from the ROM which can fail to load or save.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Structure {
+    Header,
+    Tileset,
+    Palette,
+    Gfx,
+    TileTable,
+    LevelData,
+    Room,
+    State,
+    Door,
+    DoorList,
+    SaveStation,
+    PlmPopulation,
+    EnemyPopulation,
+    EnemySet,
+    Fx,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// Failed to parse 'structure' at the SNES 'address'.
+    Parse {
+        structure: Structure,
+        address: usize,
+        error: ParseError,
+    },
+    /// Failed to decompress 'structure' at the SNES 'address'.
+    Lz5 {
+        structure: Structure,
+        address: usize,
+        error: Lz5Error,
+    },
+    /// The ROM is not the vanilla one, but only vanilla was accepted.
+    NotVanilla,
+    /// The ROM uses a 'mapping' whose data tables can't be found at the LoRom addresses of the game.
+    UnsupportedMapping { mapping: Mapping },
+    /// There is no free space left to write 'size' bytes of 'structure'.
+    OutOfFreeSpace { structure: Structure, size: usize },
+    /// The ROM can't be expanded to 'size' bytes, or is too small for its header.
+    InvalidRomSize { size: usize },
+    /// The checksum in the header doesn't match the one 'computed' from the ROM.
+    BadChecksum { header: u16, computed: u16 },
+    /// Failed to apply a patch to the ROM.
+    Patch { error: PatchError },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse {
+                structure,
+                address,
+                error,
+            } => write!(
+                f,
+                "Failed to parse {:?} at ${:06X}: {}.",
+                structure, address, error
+            ),
+            Error::Lz5 {
+                structure,
+                address,
+                error,
+            } => write!(
+                f,
+                "Failed to decompress {:?} at ${:06X}: {}.",
+                structure, address, error
+            ),
+            Error::NotVanilla => write!(f, "ROM is not the vanilla one."),
+            Error::UnsupportedMapping { mapping } => {
+                write!(
+                    f,
+                    "ROM mapping {:?} is not supported, only LoRom is.",
+                    mapping
+                )
+            }
+            Error::OutOfFreeSpace { structure, size } => write!(
+                f,
+                "Not enough free space to save {:?} of {} bytes.",
+                structure, size
+            ),
+            Error::InvalidRomSize { size } => write!(f, "Invalid ROM size of {} bytes.", size),
+            Error::BadChecksum { header, computed } => write!(
+                f,
+                "Checksum ${:04X} in header doesn't match the ROM's ${:04X}.",
+                header, computed
+            ),
+            Error::Patch { error } => write!(f, "Failed to apply patch: {}.", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Errors tell which structure failed, where and why.
+    #[test]
+    fn display_error_with_context() {
+        assert_eq!(
+            Error::Lz5 {
+                structure: Structure::LevelData,
+                address: 0xC2C2BB,
+                error: Lz5Error::BadCommand,
+            }
+            .to_string(),
+            "Failed to decompress LevelData at $C2C2BB: bad LZ5 command."
+        );
+        assert_eq!(
+            Error::OutOfFreeSpace {
+                structure: Structure::Room,
+                size: 0x40,
+            }
+            .to_string(),
+            "Not enough free space to save Room of 64 bytes."
+        );
+        assert_eq!(
+            Error::UnsupportedMapping {
+                mapping: Mapping::HiRom,
+            }
+            .to_string(),
+            "ROM mapping HiRom is not supported, only LoRom is."
+        );
+    }
+}
@@ -1,15 +1,42 @@
-use std::{error::Error, fs};
+mod cli;
 
-use zen::super_metroid;
+use std::{env, process};
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let mut sm = super_metroid::load_unheadered_rom(fs::read(
-        "/home/rondao/dev/snes_data/test/Super Metroid (JU) [!].smc",
-    )?)?;
+use cli::{commands, Arguments, UsageError, USAGE};
 
-    sm.save_to_rom();
-    sm.save_to_file("/home/rondao/roms/snes/MyHack.smc")
-        .unwrap();
+/// Exit codes, with usage errors apart from failures of the command itself.
+const EXIT_FAILURE: i32 = 1;
+const EXIT_USAGE: i32 = 2;
 
-    Ok(())
+fn main() {
+    let mut args = env::args().skip(1);
+    let command = args.next().unwrap_or_default();
+
+    let result = Arguments::parse(args)
+        .map_err(Into::into)
+        .and_then(|arguments| match command.as_str() {
+            "info" => commands::info(&arguments),
+            "list-rooms" => commands::list_rooms(&arguments),
+            "render-room" => commands::render_room(&arguments),
+            "render-tileset" => commands::render_tileset(&arguments),
+            "export" => commands::export(&arguments),
+            "build" => commands::build(&arguments),
+            "patch" => commands::patch(&arguments),
+            "verify" => commands::verify(&arguments),
+            "help" | "--help" | "-h" => {
+                println!("{}", USAGE);
+                Ok(())
+            }
+            "" => Err(UsageError("Missing command.".to_string()).into()),
+            _ => Err(UsageError(format!("Unknown command '{}'.", command)).into()),
+        });
+
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        if error.is::<UsageError>() {
+            eprintln!("\n{}", USAGE);
+            process::exit(EXIT_USAGE);
+        }
+        process::exit(EXIT_FAILURE);
+    }
 }
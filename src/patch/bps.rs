@@ -0,0 +1,345 @@
+use std::convert::{TryFrom, TryInto};
+
+use super::PatchError;
+
+/// BPS format reference: https://github.com/blakesmith/rombp/blob/master/docs/bps_spec.md
+pub const HEADER: &[u8] = b"BPS1";
+/// Checksums of the source, target and patch.
+const FOOTER_SIZE: usize = 12;
+
+const SOURCE_READ: usize = 0;
+const TARGET_READ: usize = 1;
+const SOURCE_COPY: usize = 2;
+const TARGET_COPY: usize = 3;
+
+/// Reading from source needs its own action, so it's only worth for at least two bytes.
+const MIN_SOURCE_READ_SIZE: usize = 2;
+/// Runs of the same byte at least this long are copied from the target instead of stored in the patch.
+const MIN_RUN_SIZE: usize = 4;
+/// Most bytes reserved for the target before applying, whatever size the patch claims.
+const MAX_TARGET_CAPACITY: usize = 0x100_0000;
+
+/// Create a patch turning 'source' into 'target'.
+/// Bytes are read from the same address in 'source', or runs of the same byte are copied from 'target'.
+pub fn create(source: &[u8], target: &[u8]) -> Vec<u8> {
+    let mut patch = HEADER.to_vec();
+    encode_number(&mut patch, source.len());
+    encode_number(&mut patch, target.len());
+    encode_number(&mut patch, 0); // No metadata.
+
+    let mut target_relative_offset = 0;
+    let mut target_read: Vec<u8> = Vec::new();
+    let flush_target_read = |patch: &mut Vec<u8>, target_read: &mut Vec<u8>| {
+        if !target_read.is_empty() {
+            encode_action(patch, TARGET_READ, target_read.len());
+            patch.append(target_read);
+        }
+    };
+
+    let mut address = 0;
+    while address < target.len() {
+        let same_as_source = target[address..]
+            .iter()
+            .zip(source.get(address..).unwrap_or_default())
+            .take_while(|(byte, source_byte)| byte == source_byte)
+            .count();
+        let run = match address.checked_sub(1) {
+            Some(previous_address) => target[address..]
+                .iter()
+                .take_while(|byte| **byte == target[previous_address])
+                .count(),
+            None => 0,
+        };
+
+        if same_as_source >= MIN_SOURCE_READ_SIZE {
+            flush_target_read(&mut patch, &mut target_read);
+            encode_action(&mut patch, SOURCE_READ, same_as_source);
+            address += same_as_source;
+        } else if run >= MIN_RUN_SIZE {
+            // Copy from the previous byte, which repeats it as the copy moves forward.
+            flush_target_read(&mut patch, &mut target_read);
+            encode_action(&mut patch, TARGET_COPY, run);
+            encode_offset(
+                &mut patch,
+                (address - 1) as isize - target_relative_offset as isize,
+            );
+            target_relative_offset = address - 1 + run;
+            address += run;
+        } else {
+            target_read.push(target[address]);
+            address += 1;
+        }
+    }
+    flush_target_read(&mut patch, &mut target_read);
+
+    patch.extend(crc32(source).to_le_bytes());
+    patch.extend(crc32(target).to_le_bytes());
+    patch.extend(crc32(&patch).to_le_bytes());
+    patch
+}
+
+/// Apply 'patch' to 'source', after checking both match their checksums.
+pub fn apply(source: &[u8], patch: &[u8]) -> Result<Vec<u8>, PatchError> {
+    if !patch.starts_with(HEADER) {
+        return Err(PatchError::BadHeader);
+    }
+    if patch.len() < HEADER.len() + FOOTER_SIZE {
+        return Err(PatchError::Truncated);
+    }
+
+    let footer = patch.len() - FOOTER_SIZE;
+    let read_crc =
+        |position: usize| u32::from_le_bytes(patch[position..position + 4].try_into().unwrap());
+    if crc32(&patch[..footer + 8]) != read_crc(footer + 8) || crc32(source) != read_crc(footer) {
+        return Err(PatchError::BadChecksum);
+    }
+
+    let mut position = HEADER.len();
+    let _source_size = decode_number(patch, &mut position)?;
+    let target_size = decode_number(patch, &mut position)?;
+    let metadata_size = decode_number(patch, &mut position)?;
+    position = position
+        .checked_add(metadata_size)
+        .ok_or(PatchError::BadRecord)?;
+
+    let mut target: Vec<u8> =
+        Vec::with_capacity(target_size.min(source.len().max(MAX_TARGET_CAPACITY)));
+    let mut source_relative_offset: isize = 0;
+    let mut target_relative_offset: isize = 0;
+    while position < footer {
+        let action = decode_number(patch, &mut position)?;
+        let number_of_bytes = (action >> 2) + 1;
+        // No record may write past the size of the target.
+        if target
+            .len()
+            .checked_add(number_of_bytes)
+            .is_none_or(|end| end > target_size)
+        {
+            return Err(PatchError::BadRecord);
+        }
+
+        match action & 0b11 {
+            SOURCE_READ => {
+                let address = target.len();
+                target.extend(read(source, address, number_of_bytes)?);
+            }
+            TARGET_READ => {
+                if footer - position.min(footer) < number_of_bytes {
+                    return Err(PatchError::Truncated);
+                }
+                target.extend(&patch[position..position + number_of_bytes]);
+                position += number_of_bytes;
+            }
+            SOURCE_COPY => {
+                let address = move_offset(&mut source_relative_offset, patch, &mut position)?;
+                target.extend(read(source, address, number_of_bytes)?);
+                source_relative_offset += number_of_bytes as isize;
+            }
+            _ => {
+                let address = move_offset(&mut target_relative_offset, patch, &mut position)?;
+                // Byte by byte, as the copy may read what it's writing.
+                for address in address..address + number_of_bytes {
+                    let byte = *target.get(address).ok_or(PatchError::BadRecord)?;
+                    target.push(byte);
+                }
+                target_relative_offset += number_of_bytes as isize;
+            }
+        }
+    }
+
+    if target.len() != target_size || crc32(&target) != read_crc(footer + 4) {
+        return Err(PatchError::BadChecksum);
+    }
+
+    Ok(target)
+}
+
+/// The 'number_of_bytes' of 'data' from 'address', failing if any is outside of it.
+fn read(data: &[u8], address: usize, number_of_bytes: usize) -> Result<&[u8], PatchError> {
+    address
+        .checked_add(number_of_bytes)
+        .and_then(|end| data.get(address..end))
+        .ok_or(PatchError::BadRecord)
+}
+
+/// Move 'relative_offset' by the next offset of 'patch', returning the address it points to.
+fn move_offset(
+    relative_offset: &mut isize,
+    patch: &[u8],
+    position: &mut usize,
+) -> Result<usize, PatchError> {
+    *relative_offset = relative_offset
+        .checked_add(decode_offset(patch, position)?)
+        .ok_or(PatchError::BadRecord)?;
+    usize::try_from(*relative_offset).map_err(|_| PatchError::BadRecord)
+}
+
+/// Numbers are stored 7 bits at a time, with the last byte flagged by its high bit.
+fn encode_number(patch: &mut Vec<u8>, mut number: usize) {
+    loop {
+        let bits = (number & 0x7F) as u8;
+        number >>= 7;
+        if number == 0 {
+            patch.push(0x80 | bits);
+            break;
+        }
+        patch.push(bits);
+        number -= 1;
+    }
+}
+
+fn decode_number(patch: &[u8], position: &mut usize) -> Result<usize, PatchError> {
+    let mut number: usize = 0;
+    let mut shift = 1;
+    loop {
+        let byte = *patch.get(*position).ok_or(PatchError::Truncated)?;
+        *position += 1;
+
+        number = ((byte & 0x7F) as usize)
+            .checked_mul(shift)
+            .and_then(|bits| number.checked_add(bits))
+            .ok_or(PatchError::BadRecord)?;
+        if byte & 0x80 != 0 {
+            return Ok(number);
+        }
+        shift = shift.checked_mul(0x80).ok_or(PatchError::BadRecord)?;
+        number = number.checked_add(shift).ok_or(PatchError::BadRecord)?;
+    }
+}
+
+fn encode_action(patch: &mut Vec<u8>, action: usize, number_of_bytes: usize) {
+    encode_number(patch, ((number_of_bytes - 1) << 2) | action);
+}
+
+/// Offsets store their sign in the lowest bit.
+fn encode_offset(patch: &mut Vec<u8>, offset: isize) {
+    encode_number(patch, (offset.unsigned_abs() << 1) | (offset < 0) as usize);
+}
+
+fn decode_offset(patch: &[u8], position: &mut usize) -> Result<isize, PatchError> {
+    let number = decode_number(patch, position)?;
+    let offset = (number >> 1) as isize;
+    Ok(if number & 1 == 1 { -offset } else { offset })
+}
+
+/// CRC-32 as used by BPS and ZIP files.
+pub fn crc32(data: &[u8]) -> u32 {
+    let table: Vec<u32> = (0..256)
+        .map(|index| {
+            (0..8).fold(index, |crc, _| {
+                if crc & 1 == 1 {
+                    (crc >> 1) ^ 0xEDB8_8320
+                } else {
+                    crc >> 1
+                }
+            })
+        })
+        .collect();
+
+    !data.iter().fold(0xFFFF_FFFF, |crc, byte| {
+        table[((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encode numbers and offsets, then decode them back.
+    #[test]
+    fn encode_and_decode_numbers() {
+        let mut patch = Vec::new();
+        for number in [0, 0x7F, 0x80, 0x407F, 0x4080, 0x30_0000] {
+            encode_number(&mut patch, number);
+        }
+        encode_offset(&mut patch, -5);
+        assert_eq!(patch[..4], [0x80, 0xFF, 0x00, 0x80]);
+
+        let mut position = 0;
+        for number in [0, 0x7F, 0x80, 0x407F, 0x4080, 0x30_0000] {
+            assert_eq!(decode_number(&patch, &mut position).unwrap(), number);
+        }
+        assert_eq!(decode_offset(&patch, &mut position).unwrap(), -5);
+        assert_eq!(position, patch.len());
+
+        // Numbers too big for 'usize' are rejected instead of overflowing.
+        assert_eq!(
+            decode_number(&[0x7F; 16], &mut 0),
+            Err(PatchError::BadRecord)
+        );
+    }
+
+    /// Checksum of known data.
+    #[test]
+    fn compute_crc32() {
+        assert_eq!(crc32(b""), 0x0000_0000);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    /// Create a patch reading from source and copying runs from target, then apply it.
+    #[test]
+    fn create_and_apply_patch() {
+        let source: Vec<u8> = (0..0x40).collect();
+        let mut target = source.clone();
+        target[0x08] = 0xAA;
+        target[0x10..0x20].fill(0xBB);
+        target.extend([0xCC; 0x10]);
+
+        let patch = create(&source, &target);
+        assert_eq!(apply(&source, &patch).unwrap(), target);
+        assert!(patch.len() < 0x30);
+
+        target.truncate(0x20);
+        assert_eq!(apply(&source, &create(&source, &target)).unwrap(), target);
+    }
+
+    /// Fail to apply a patch to a different source, or a patch which was modified.
+    #[test]
+    fn apply_patch_with_bad_checksum() {
+        let source = vec![0x00; 0x10];
+        let target = vec![0x01; 0x10];
+        let mut patch = create(&source, &target);
+
+        assert_eq!(apply(&target, &patch), Err(PatchError::BadChecksum));
+        patch[HEADER.len() + 3] ^= 0xFF;
+        assert_eq!(apply(&source, &patch), Err(PatchError::BadChecksum));
+        assert_eq!(apply(&source, &patch[..8]), Err(PatchError::Truncated));
+    }
+
+    /// Fail to apply records reading or writing past the end of the data, whatever their size.
+    #[test]
+    fn apply_patch_with_bad_records() {
+        let source = vec![0x00; 0x10];
+        let with_numbers = |metadata_size: usize, numbers: &[usize]| {
+            let mut patch = HEADER.to_vec();
+            encode_number(&mut patch, source.len());
+            encode_number(&mut patch, 0x10);
+            encode_number(&mut patch, metadata_size);
+            for number in numbers.iter() {
+                encode_number(&mut patch, *number);
+            }
+            patch.extend(crc32(&source).to_le_bytes());
+            patch.extend([0; 4]);
+            patch.extend(crc32(&patch).to_le_bytes());
+            patch
+        };
+
+        // A huge metadata size, and a source copy from the end of the addresses.
+        assert_eq!(
+            apply(&source, &with_numbers(usize::MAX, &[])),
+            Err(PatchError::BadRecord)
+        );
+        assert_eq!(
+            apply(&source, &with_numbers(0, &[SOURCE_COPY, usize::MAX - 1])),
+            Err(PatchError::BadRecord)
+        );
+        // A target copy repeating the first byte far past the target size.
+        assert_eq!(
+            apply(
+                &source,
+                &with_numbers(0, &[SOURCE_READ, usize::MAX & !0b11 | TARGET_COPY, 0])
+            ),
+            Err(PatchError::BadRecord)
+        );
+    }
+}
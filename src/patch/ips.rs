@@ -0,0 +1,211 @@
+use super::PatchError;
+
+/// IPS format reference: https://zerosoft.zophar.net/ips.php
+pub const HEADER: &[u8] = b"PATCH";
+const FOOTER: &[u8] = b"EOF";
+
+/// Records have a three bytes address and up to $FFFF bytes of data.
+const MAX_ADDRESS: usize = 0xFF_FFFF;
+const MAX_RECORD_SIZE: usize = 0xFFFF;
+/// A record starting at this address would be read as the footer.
+const EOF_ADDRESS: usize = 0x45_4F46;
+
+/// Each record takes 5 bytes, so smaller gaps of unchanged bytes are kept in the same record.
+const MAX_UNCHANGED_GAP: usize = 5;
+/// A RLE record takes 8 bytes, so only longer runs of the same byte are worth it.
+const MIN_RLE_SIZE: usize = 9;
+
+/// Create a patch turning 'source' into 'target', using RLE records for runs of the same byte.
+/// Only the first 16 MB of 'target' can be addressed.
+pub fn create(source: &[u8], target: &[u8]) -> Vec<u8> {
+    let mut patch = HEADER.to_vec();
+    let target_size = target.len().min(MAX_ADDRESS + 1);
+    let is_changed = |address: usize| source.get(address) != Some(&target[address]);
+
+    let mut address = 0;
+    while address < target_size {
+        if !is_changed(address) {
+            address += 1;
+            continue;
+        }
+
+        // Extend the changed region until a gap of unchanged bytes too big to keep.
+        let start = address;
+        let mut end = address + 1;
+        while end < target_size {
+            match (end..target_size.min(end + MAX_UNCHANGED_GAP + 1)).find(|a| is_changed(*a)) {
+                Some(changed_address) => end = changed_address + 1,
+                None => break,
+            }
+        }
+
+        push_records(&mut patch, target, start, end);
+        address = end;
+    }
+
+    patch.extend(FOOTER);
+
+    // Truncate extension.
+    if target.len() < source.len() {
+        patch.extend(&(target.len() as u32).to_be_bytes()[1..]);
+    }
+
+    patch
+}
+
+/// Push records writing 'target[start..end]'.
+fn push_records(patch: &mut Vec<u8>, target: &[u8], start: usize, end: usize) {
+    let run_size = |address: usize| {
+        target[address..end.min(address + MAX_RECORD_SIZE)]
+            .iter()
+            .take_while(|byte| **byte == target[address])
+            .count()
+    };
+
+    let mut address = start;
+    while address < end {
+        let run = run_size(address);
+        if run >= MIN_RLE_SIZE && address != EOF_ADDRESS {
+            patch.extend(&(address as u32).to_be_bytes()[1..]);
+            patch.extend([0x00, 0x00]);
+            patch.extend((run as u16).to_be_bytes());
+            patch.push(target[address]);
+            address += run;
+            continue;
+        }
+
+        // Any record at the "EOF" address starts a byte before it, writing the same byte again.
+        let record_start = if address == EOF_ADDRESS {
+            address - 1
+        } else {
+            address
+        };
+        let mut record_end = address + 1;
+        while record_end < end
+            && record_end - record_start < MAX_RECORD_SIZE
+            && run_size(record_end) < MIN_RLE_SIZE
+        {
+            record_end += 1;
+        }
+
+        patch.extend(&(record_start as u32).to_be_bytes()[1..]);
+        patch.extend(((record_end - record_start) as u16).to_be_bytes());
+        patch.extend(&target[record_start..record_end]);
+        address = record_end;
+    }
+}
+
+/// Apply 'patch' to 'source', growing it if a record writes past its end.
+pub fn apply(source: &[u8], patch: &[u8]) -> Result<Vec<u8>, PatchError> {
+    if !patch.starts_with(HEADER) {
+        return Err(PatchError::BadHeader);
+    }
+
+    let read = |position: usize, size: usize| {
+        patch
+            .get(position..position + size)
+            .ok_or(PatchError::Truncated)
+    };
+    let read_number = |position: usize, size: usize| -> Result<usize, PatchError> {
+        Ok(read(position, size)?
+            .iter()
+            .fold(0, |number, byte| (number << 8) + *byte as usize))
+    };
+
+    let mut target = source.to_vec();
+    let mut position = HEADER.len();
+    loop {
+        if read(position, 3)? == FOOTER {
+            position += 3;
+            break;
+        }
+
+        let address = read_number(position, 3)?;
+        let size = read_number(position + 3, 2)?;
+        position += 5;
+
+        let data = if size == 0 {
+            // RLE record.
+            let run = read_number(position, 2)?;
+            let byte = read(position + 2, 1)?[0];
+            position += 3;
+            vec![byte; run]
+        } else {
+            position += size;
+            read(position - size, size)?.to_vec()
+        };
+
+        if target.len() < address + data.len() {
+            target.resize(address + data.len(), 0);
+        }
+        target[address..address + data.len()].copy_from_slice(&data);
+    }
+
+    // Truncate extension.
+    if let Ok(size) = read_number(position, 3) {
+        target.truncate(size);
+    }
+
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create a patch with normal and RLE records, then apply it.
+    #[test]
+    fn create_and_apply_patch() {
+        let source = vec![0x00; 0x40];
+        let mut target = source.clone();
+        target[0x02] = 0x11;
+        target[0x05] = 0x22;
+        target[0x20..0x30].fill(0x33);
+
+        let patch = create(&source, &target);
+        #[rustfmt::skip]
+        assert_eq!(
+            patch,
+            [
+                b'P', b'A', b'T', b'C', b'H',
+                0x00, 0x00, 0x02, 0x00, 0x04, 0x11, 0x00, 0x00, 0x22, // Normal record.
+                0x00, 0x00, 0x20, 0x00, 0x00, 0x00, 0x10, 0x33,       // RLE record.
+                b'E', b'O', b'F',
+            ]
+        );
+        assert_eq!(apply(&source, &patch).unwrap(), target);
+    }
+
+    /// Grow and truncate the source to the target size.
+    #[test]
+    fn create_and_apply_patch_changing_size() {
+        let source: Vec<u8> = (0..0x20).collect();
+
+        let target: Vec<u8> = (0..0x30).collect();
+        assert_eq!(apply(&source, &create(&source, &target)).unwrap(), target);
+
+        let target: Vec<u8> = (0..0x10).collect();
+        let patch = create(&source, &target);
+        assert_eq!(patch, [HEADER, FOOTER, &[0x00, 0x00, 0x10]].concat());
+        assert_eq!(apply(&source, &patch).unwrap(), target);
+    }
+
+    /// Never start a record at the address which reads as "EOF".
+    #[test]
+    fn create_patch_around_eof_address() {
+        let source = vec![0x00; EOF_ADDRESS + 0x10];
+        let mut target = source.clone();
+        target[EOF_ADDRESS] = 0x01;
+
+        let patch = create(&source, &target);
+        assert_eq!(patch[HEADER.len()..HEADER.len() + 3], [0x45, 0x4F, 0x45]);
+        assert_eq!(apply(&source, &patch).unwrap(), target);
+    }
+
+    /// Fail to apply a patch without its footer.
+    #[test]
+    fn apply_truncated_patch() {
+        let patch = [HEADER, &[0x00, 0x00, 0x02, 0x00, 0x04, 0x11]].concat();
+        assert_eq!(apply(&[0x00; 4], &patch), Err(PatchError::Truncated));
+    }
+}
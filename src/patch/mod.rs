@@ -0,0 +1,64 @@
+pub mod bps;
+pub mod ips;
+
+use std::{error::Error, fmt};
+
+/// Why a patch could not be applied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PatchError {
+    /// The patch doesn't start with a known format.
+    BadHeader,
+    /// The patch ended before all its records.
+    Truncated,
+    /// A record reads from outside of the source or target data.
+    BadRecord,
+    /// The source, target or patch data doesn't match its checksum.
+    BadChecksum,
+}
+
+impl Error for PatchError {}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PatchError::BadHeader => write!(f, "unknown patch format"),
+            PatchError::Truncated => write!(f, "patch is truncated"),
+            PatchError::BadRecord => write!(f, "bad patch record"),
+            PatchError::BadChecksum => write!(f, "patch checksum doesn't match"),
+        }
+    }
+}
+
+/// Apply an IPS or BPS 'patch' to 'source', detecting its format by its header.
+pub fn apply(source: &[u8], patch: &[u8]) -> Result<Vec<u8>, PatchError> {
+    if patch.starts_with(ips::HEADER) {
+        ips::apply(source, patch)
+    } else if patch.starts_with(bps::HEADER) {
+        bps::apply(source, patch)
+    } else {
+        Err(PatchError::BadHeader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Apply patches of both formats, and reject unknown ones.
+    #[test]
+    fn apply_patch_of_any_format() {
+        let source: Vec<u8> = (0..0x100).map(|i| i as u8).collect();
+        let mut target = source.clone();
+        target[0x10..0x20].fill(0xAA);
+
+        assert_eq!(
+            apply(&source, &ips::create(&source, &target)).unwrap(),
+            target
+        );
+        assert_eq!(
+            apply(&source, &bps::create(&source, &target)).unwrap(),
+            target
+        );
+        assert_eq!(apply(&source, b"UPS1"), Err(PatchError::BadHeader));
+    }
+}
@@ -0,0 +1,851 @@
+pub mod text;
+
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    graphics::{gfx::Gfx, palette},
+    image::gfx_from_indexed_image,
+    super_metroid::{
+        self,
+        address::NUMBER_OF_AREAS,
+        door::Door,
+        level_data,
+        progress::{BossFlags, Event},
+        room::{Condition, Room, StateCondition},
+        save_station::SaveStation,
+        state::State,
+        tile_table,
+        tileset::Tileset,
+        LoadOptions, SuperMetroid,
+    },
+    Error, ParseError,
+};
+
+use image::DynamicImage;
+use text::{hex, Section, TextError};
+
+/// A project is a folder with one file for each asset of the ROM.
+/// Assets stored in their own file are named after their address, e.g. "palettes/C2AD7C.tpl".
+pub const PALETTES: &str = "palettes";
+pub const GRAPHICS: &str = "gfx";
+pub const TILE_TABLES: &str = "tile_tables";
+pub const LEVELS: &str = "levels";
+pub const ROOMS: &str = "rooms";
+pub const DOORS: &str = "doors.txt";
+pub const DOOR_LISTS: &str = "door_lists.txt";
+pub const SAVE_STATIONS: &str = "save_stations.txt";
+pub const TILESETS: &str = "tilesets.txt";
+/// Gfx and Tile Table shared by the Tilesets using CRE, at fixed addresses.
+pub const CRE_GRAPHICS: &str = "cre_gfx.png";
+pub const CRE_TILE_TABLE: &str = "cre_tile_table.ttb";
+
+/// Why a project could not be exported or imported.
+#[derive(Debug)]
+pub enum ProjectError {
+    /// Failed to read or write 'file'.
+    Io { file: PathBuf, error: io::Error },
+    /// The name of 'file' is not the address of its asset.
+    BadFileName { file: PathBuf },
+    /// Failed to read the text of 'file'.
+    Text { file: PathBuf, error: TextError },
+    /// Failed to parse the asset in 'file'.
+    Asset { file: PathBuf, error: ParseError },
+    /// Failed to load the base ROM, or to save the project into it.
+    Rom { error: Error },
+}
+
+impl fmt::Display for ProjectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProjectError::Io { file, error } => {
+                write!(f, "Failed to access {}: {}.", file.display(), error)
+            }
+            ProjectError::BadFileName { file } => {
+                write!(f, "File {} is not named after an address.", file.display())
+            }
+            ProjectError::Text { file, error } => {
+                write!(f, "Failed to read {}: {}.", file.display(), error)
+            }
+            ProjectError::Asset { file, error } => {
+                write!(f, "Failed to parse {}: {}.", file.display(), error)
+            }
+            ProjectError::Rom { error } => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for ProjectError {}
+
+impl From<Error> for ProjectError {
+    fn from(error: Error) -> Self {
+        ProjectError::Rom { error }
+    }
+}
+
+/// Write every asset of 'sm' to its own file in the 'project' folder.
+/// Palettes also get a PNG preview, which is not imported back.
+/// Gfx are gray PNGs with one shade for each color index.
+pub fn export(sm: &SuperMetroid, project: &Path) -> Result<(), ProjectError> {
+    for folder in [PALETTES, GRAPHICS, TILE_TABLES, LEVELS, ROOMS].iter() {
+        let folder = project.join(folder);
+        fs::create_dir_all(&folder).map_err(|error| ProjectError::Io {
+            file: folder,
+            error,
+        })?;
+    }
+
+    for (address, palette) in sm.palettes.iter() {
+        write(
+            &asset_file(project, PALETTES, *address, "tpl"),
+            palette.to_tpl(),
+        )?;
+        save_image(
+            DynamicImage::ImageRgb8(palette.to_image()),
+            &asset_file(project, PALETTES, *address, "png"),
+        )?;
+    }
+    for (address, gfx) in sm.graphics.iter() {
+        save_image(
+            DynamicImage::ImageLuma8(gfx.to_indexed_image()),
+            &asset_file(project, GRAPHICS, *address, "png"),
+        )?;
+    }
+    for (address, tile_table) in sm.tile_tables.iter() {
+        write(
+            &asset_file(project, TILE_TABLES, *address, "ttb"),
+            tile_table::to_bytes(tile_table),
+        )?;
+    }
+    save_image(
+        DynamicImage::ImageLuma8(sm.cre_gfx.to_indexed_image()),
+        &project.join(CRE_GRAPHICS),
+    )?;
+    write(
+        &project.join(CRE_TILE_TABLE),
+        tile_table::to_bytes(&sm.cre_tileset),
+    )?;
+    for (address, level) in sm.levels.iter() {
+        write(
+            &asset_file(project, LEVELS, *address, "lvl"),
+            level.to_bytes(),
+        )?;
+    }
+    for (address, room) in sm.rooms.iter() {
+        write(
+            &asset_file(project, ROOMS, *address, "txt"),
+            text::to_text(&room_to_sections(room, &sm.states)),
+        )?;
+    }
+
+    write(
+        &project.join(DOORS),
+        text::to_text(&sorted_by_address(&sm.doors, door_to_section)),
+    )?;
+    write(
+        &project.join(DOOR_LISTS),
+        text::to_text(&sorted_by_address(&sm.door_lists, |address, door_list| {
+            let mut section = Section::new("door_list", Some(address));
+            section.push("doors", hex_list(door_list.iter().map(|d| *d as usize), 4));
+            section
+        })),
+    )?;
+    write(
+        &project.join(SAVE_STATIONS),
+        text::to_text(
+            &sm.save_stations
+                .iter()
+                .enumerate()
+                .flat_map(|(area, save_stations)| {
+                    save_stations
+                        .iter()
+                        .map(move |save_station| save_station_to_section(area, save_station))
+                })
+                .collect::<Vec<Section>>(),
+        ),
+    )?;
+    write(
+        &project.join(TILESETS),
+        text::to_text(
+            &sm.tilesets
+                .iter()
+                .enumerate()
+                .map(|(index, tileset)| tileset_to_section(index, tileset))
+                .collect::<Vec<Section>>(),
+        ),
+    )?;
+
+    Ok(())
+}
+
+/// Replace the assets of 'sm' with every one found in the 'project' folder.
+/// Missing files are skipped, keeping the assets 'sm' already has.
+pub fn import(sm: &mut SuperMetroid, project: &Path) -> Result<(), ProjectError> {
+    for (address, file) in asset_files(project, PALETTES, "tpl")? {
+        let palette = palette::from_bytes(&read(&file)?)
+            .map_err(|error| ProjectError::Asset { file, error })?;
+        sm.palettes.insert(address, palette);
+    }
+    for (address, file) in asset_files(project, GRAPHICS, "png")? {
+        sm.graphics.insert(address, read_gfx(file)?);
+    }
+    for (address, file) in asset_files(project, TILE_TABLES, "ttb")? {
+        sm.tile_tables
+            .insert(address, tile_table::from_bytes(&read(&file)?));
+    }
+    if project.join(CRE_GRAPHICS).exists() {
+        sm.cre_gfx = read_gfx(project.join(CRE_GRAPHICS))?;
+    }
+    if project.join(CRE_TILE_TABLE).exists() {
+        sm.cre_tileset = tile_table::from_bytes(&read(&project.join(CRE_TILE_TABLE))?);
+    }
+    for (address, file) in asset_files(project, LEVELS, "lvl")? {
+        let source = read(&file)?;
+        // The layer size only tells how many blocks there are, so a second layer is known by the data left.
+        let number_of_blocks = source.get(..2).map_or(0, |size| {
+            u16::from_le_bytes([size[0], size[1]]) as usize / 2
+        });
+        let has_layer2 = source.len() >= 2 + number_of_blocks * 5;
+        let level = level_data::load_from_bytes(&source, has_layer2)
+            .map_err(|error| ProjectError::Asset { file, error })?;
+        sm.levels.insert(address, level);
+    }
+    for (address, file) in asset_files(project, ROOMS, "txt")? {
+        let (room, states) = room_from_sections(&parse(&file)?)
+            .map_err(|error| ProjectError::Text { file, error })?;
+        sm.rooms.insert(address, room);
+        sm.states.extend(states);
+    }
+
+    if let Some(sections) = parse_if_exists(&project.join(DOORS))? {
+        for section in sections.iter() {
+            let (address, door) =
+                door_from_section(section).map_err(|error| ProjectError::Text {
+                    file: project.join(DOORS),
+                    error,
+                })?;
+            sm.doors.insert(address, door);
+        }
+    }
+    if let Some(sections) = parse_if_exists(&project.join(DOOR_LISTS))? {
+        for section in sections.iter() {
+            let door_list = section_address(section)
+                .and_then(|address| Ok((address, section.numbers("doors")?)))
+                .map_err(|error| ProjectError::Text {
+                    file: project.join(DOOR_LISTS),
+                    error,
+                })?;
+            sm.door_lists.insert(
+                door_list.0,
+                door_list.1.iter().map(|door| *door as u16).collect(),
+            );
+        }
+    }
+    if let Some(sections) = parse_if_exists(&project.join(SAVE_STATIONS))? {
+        let mut save_stations: Vec<Vec<SaveStation>> = vec![Vec::new(); NUMBER_OF_AREAS];
+        for section in sections.iter() {
+            let (area, save_station) =
+                save_station_from_section(section).map_err(|error| ProjectError::Text {
+                    file: project.join(SAVE_STATIONS),
+                    error,
+                })?;
+            if save_stations.len() <= area {
+                save_stations.resize(area + 1, Vec::new());
+            }
+            save_stations[area].push(save_station);
+        }
+        sm.save_stations = save_stations;
+    }
+    if let Some(sections) = parse_if_exists(&project.join(TILESETS))? {
+        for section in sections.iter() {
+            let (index, tileset) =
+                tileset_from_section(section).map_err(|error| ProjectError::Text {
+                    file: project.join(TILESETS),
+                    error,
+                })?;
+            if let Some(old_tileset) = sm.tilesets.get_mut(index) {
+                *old_tileset = tileset;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Load the 'rom' the project was exported from, replace its assets with the 'project' ones,
+/// then recompress and relink all of them into the ROM.
+pub fn build(rom: Vec<u8>, project: &Path) -> Result<SuperMetroid, ProjectError> {
+    let mut sm = super_metroid::load_rom(rom, LoadOptions::default())?;
+    import(&mut sm, project)?;
+    sm.save_to_rom()?;
+    Ok(sm)
+}
+
+fn asset_file(project: &Path, folder: &str, address: usize, extension: &str) -> PathBuf {
+    project
+        .join(folder)
+        .join(format!("{:06X}.{}", address, extension))
+}
+
+/// Every file of 'folder' with 'extension', along with the address in its name.
+fn asset_files(
+    project: &Path,
+    folder: &str,
+    extension: &str,
+) -> Result<Vec<(usize, PathBuf)>, ProjectError> {
+    let folder = project.join(folder);
+    let entries = match fs::read_dir(&folder) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => {
+            return Err(ProjectError::Io {
+                file: folder,
+                error,
+            })
+        }
+    };
+
+    let mut files = Vec::new();
+    for entry in entries {
+        let file = entry
+            .map_err(|error| ProjectError::Io {
+                file: folder.clone(),
+                error,
+            })?
+            .path();
+        if file.extension().and_then(|e| e.to_str()) != Some(extension) {
+            continue;
+        }
+
+        match file
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| usize::from_str_radix(stem, 16).ok())
+        {
+            Some(address) => files.push((address, file)),
+            None => return Err(ProjectError::BadFileName { file }),
+        }
+    }
+    files.sort_unstable();
+    Ok(files)
+}
+
+fn read(file: &Path) -> Result<Vec<u8>, ProjectError> {
+    fs::read(file).map_err(|error| ProjectError::Io {
+        file: file.to_path_buf(),
+        error,
+    })
+}
+
+fn write(file: &Path, data: impl AsRef<[u8]>) -> Result<(), ProjectError> {
+    fs::write(file, data).map_err(|error| ProjectError::Io {
+        file: file.to_path_buf(),
+        error,
+    })
+}
+
+/// Gfx from the gray PNG 'file', with one shade for each color index.
+fn read_gfx(file: PathBuf) -> Result<Gfx, ProjectError> {
+    image::open(&file)
+        .map_err(|_| ParseError::BadHeader)
+        .and_then(|image| gfx_from_indexed_image(&image.to_luma8()).ok_or(ParseError::InvalidSize))
+        .map_err(|error| ProjectError::Asset { file, error })
+}
+
+fn save_image(image: DynamicImage, file: &Path) -> Result<(), ProjectError> {
+    image.save(file).map_err(|error| ProjectError::Io {
+        file: file.to_path_buf(),
+        error: io::Error::other(error),
+    })
+}
+
+fn parse(file: &Path) -> Result<Vec<Section>, ProjectError> {
+    let data = read(file)?;
+    let text = String::from_utf8_lossy(&data);
+    text::parse(&text).map_err(|error| ProjectError::Text {
+        file: file.to_path_buf(),
+        error,
+    })
+}
+
+fn parse_if_exists(file: &Path) -> Result<Option<Vec<Section>>, ProjectError> {
+    if file.exists() {
+        parse(file).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+fn sorted_by_address<T>(
+    assets: &HashMap<usize, T>,
+    to_section: impl Fn(usize, &T) -> Section,
+) -> Vec<Section> {
+    let mut addresses: Vec<usize> = assets.keys().copied().collect();
+    addresses.sort_unstable();
+    addresses
+        .into_iter()
+        .map(|address| to_section(address, &assets[&address]))
+        .collect()
+}
+
+fn hex_list(values: impl Iterator<Item = usize>, digits: usize) -> String {
+    values
+        .map(|value| hex(value, digits))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn section_address(section: &Section) -> Result<usize, TextError> {
+    section.address.ok_or_else(|| TextError::MissingField {
+        line: section.line,
+        field: "address".to_string(),
+    })
+}
+
+/// The Room fields, followed by one section for each State with its condition.
+fn room_to_sections(room: &Room, states: &HashMap<usize, State>) -> Vec<Section> {
+    let mut room_section = Section::new("", None);
+    room_section
+        .push("index", hex(room.index as usize, 2))
+        .push("area", hex(room.area as usize, 2))
+        .push(
+            "map_position",
+            hex_list(
+                [room.map_position.0, room.map_position.1]
+                    .iter()
+                    .map(|p| *p as usize),
+                2,
+            ),
+        )
+        .push("width", hex(room.width as usize, 2))
+        .push("height", hex(room.height as usize, 2))
+        .push("up_scroller", hex(room.up_scroller as usize, 2))
+        .push("down_scroller", hex(room.down_scroller as usize, 2))
+        .push("cre_bitset", hex(room.cre_bitset as usize, 2))
+        .push("doors", hex(room.doors as usize, 4));
+
+    let mut sections = vec![room_section];
+    for state_condition in room.state_conditions.iter() {
+        let state = states
+            .get(&(state_condition.state_address as usize))
+            .copied()
+            .unwrap_or_default();
+
+        let mut section = Section::new("state", Some(state_condition.state_address as usize));
+        section
+            .push("condition", condition_to_text(&state_condition.condition))
+            .push("level_address", hex(state.level_address as usize, 6))
+            .push("tileset", hex(state.tileset as usize, 2))
+            .push("music_data_index", hex(state.music_data_index as usize, 2))
+            .push("music_track", hex(state.music_track as usize, 2))
+            .push("fx", hex(state.fx as usize, 4))
+            .push("enemy_population", hex(state.enemy_population as usize, 4))
+            .push("enemy_set", hex(state.enemy_set as usize, 4))
+            .push("layer_2_x_scroll", hex(state.layer_2_x_scroll as usize, 2))
+            .push("layer_2_y_scroll", hex(state.layer_2_y_scroll as usize, 2))
+            .push("scroll", hex(state.scroll as usize, 4))
+            .push(
+                "special_x_ray_blocks",
+                hex(state.special_x_ray_blocks as usize, 4),
+            )
+            .push("main_asm", hex(state.main_asm as usize, 4))
+            .push("plm_population", hex(state.plm_population as usize, 4))
+            .push(
+                "library_background",
+                hex(state.library_background as usize, 4),
+            )
+            .push("setup_asm", hex(state.setup_asm as usize, 4));
+        sections.push(section);
+    }
+    sections
+}
+
+fn room_from_sections(sections: &[Section]) -> Result<(Room, Vec<(usize, State)>), TextError> {
+    let room_section = sections
+        .first()
+        .filter(|section| section.name.is_empty())
+        .ok_or(TextError::Syntax { line: 1 })?;
+    let map_position = room_section.numbers("map_position")?;
+    if map_position.len() != 2 {
+        return Err(TextError::MissingField {
+            line: room_section.line,
+            field: "map_position".to_string(),
+        });
+    }
+
+    let mut room = Room {
+        index: room_section.number("index")? as u8,
+        area: room_section.number("area")? as u8,
+        map_position: (map_position[0] as u8, map_position[1] as u8),
+        width: room_section.number("width")? as u8,
+        height: room_section.number("height")? as u8,
+        up_scroller: room_section.number("up_scroller")? as u8,
+        down_scroller: room_section.number("down_scroller")? as u8,
+        cre_bitset: room_section.number("cre_bitset")? as u8,
+        doors: room_section.number("doors")? as u16,
+        state_conditions: Vec::new(),
+    };
+
+    let mut states = Vec::new();
+    for section in sections[1..].iter() {
+        let state_address = section_address(section)?;
+        room.state_conditions.push(StateCondition {
+            condition: section.parse("condition", condition_from_text)?,
+            state_address: state_address as u16,
+        });
+        states.push((
+            state_address,
+            State {
+                level_address: section.number("level_address")? as u32,
+                tileset: section.number("tileset")? as u8,
+                music_data_index: section.number("music_data_index")? as u8,
+                music_track: section.number("music_track")? as u8,
+                fx: section.number("fx")? as u16,
+                enemy_population: section.number("enemy_population")? as u16,
+                enemy_set: section.number("enemy_set")? as u16,
+                layer_2_x_scroll: section.number("layer_2_x_scroll")? as u8,
+                layer_2_y_scroll: section.number("layer_2_y_scroll")? as u8,
+                scroll: section.number("scroll")? as u16,
+                special_x_ray_blocks: section.number("special_x_ray_blocks")? as u16,
+                main_asm: section.number("main_asm")? as u16,
+                plm_population: section.number("plm_population")? as u16,
+                library_background: section.number("library_background")? as u16,
+                setup_asm: section.number("setup_asm")? as u16,
+            },
+        ));
+    }
+
+    Ok((room, states))
+}
+
+/// The Condition name, followed by its parameter if it has one.
+/// Vanilla events are written by name, other ones by their index.
+fn condition_to_text(condition: &Condition) -> String {
+    match condition {
+        Condition::DoorPointerIs(door) => format!("DoorPointerIs {}", hex(*door as usize, 4)),
+        Condition::EventSet(Event::Other(index)) => {
+            format!("EventSet {}", hex(*index as usize, 2))
+        }
+        Condition::EventSet(event) => format!("EventSet {:?}", event),
+        Condition::BossDead(bosses) => format!("BossDead {}", hex(bosses.0 as usize, 2)),
+        Condition::Unknown(pointer) => format!("Unknown {}", hex(*pointer as usize, 4)),
+        _ => format!("{:?}", condition),
+    }
+}
+
+fn condition_from_text(value: &str) -> Option<Condition> {
+    let words: Vec<&str> = value.split_whitespace().collect();
+    let number = || words.get(1).and_then(|word| text::parse_number(word));
+
+    Some(match (words.first()?, words.len()) {
+        (&"Default", 1) => Condition::Default,
+        (&"DoorPointerIs", 2) => Condition::DoorPointerIs(number()? as u16),
+        (&"MainAreaBossDead", 1) => Condition::MainAreaBossDead,
+        (&"EventSet", 2) => Condition::EventSet(
+            Event::ALL
+                .iter()
+                .find(|event| format!("{:?}", event) == words[1])
+                .copied()
+                .or_else(|| number().map(|index| Event::from(index as u8)))?,
+        ),
+        (&"BossDead", 2) => Condition::BossDead(BossFlags(number()? as u8)),
+        (&"MorphBall", 1) => Condition::MorphBall,
+        (&"MorphAndMissiles", 1) => Condition::MorphAndMissiles,
+        (&"PowerBombs", 1) => Condition::PowerBombs,
+        (&"SpeedBooster", 1) => Condition::SpeedBooster,
+        (&"Unknown", 2) => Condition::Unknown(number()? as u16),
+        _ => return None,
+    })
+}
+
+fn door_to_section(address: usize, door: &Door) -> Section {
+    let mut section = Section::new("door", Some(address));
+    section
+        .push("destination_room", hex(door.destination_room as usize, 4))
+        .push("elevator_property", hex(door.elevator_property as usize, 2))
+        .push("orientation", hex(door.orientation as usize, 2))
+        .push("x_low_byte", hex(door.x_low_byte as usize, 2))
+        .push("y_low_byte", hex(door.y_low_byte as usize, 2))
+        .push("x_high_byte", hex(door.x_high_byte as usize, 2))
+        .push("y_high_byte", hex(door.y_high_byte as usize, 2))
+        .push(
+            "samus_door_distance",
+            hex(door.samus_door_distance as usize, 4),
+        )
+        .push("custom_asm", hex(door.custom_asm as usize, 4));
+    section
+}
+
+fn door_from_section(section: &Section) -> Result<(usize, Door), TextError> {
+    Ok((
+        section_address(section)?,
+        Door {
+            destination_room: section.number("destination_room")? as u16,
+            elevator_property: section.number("elevator_property")? as u8,
+            orientation: section.number("orientation")? as u8,
+            x_low_byte: section.number("x_low_byte")? as u8,
+            y_low_byte: section.number("y_low_byte")? as u8,
+            x_high_byte: section.number("x_high_byte")? as u8,
+            y_high_byte: section.number("y_high_byte")? as u8,
+            samus_door_distance: section.number("samus_door_distance")? as u16,
+            custom_asm: section.number("custom_asm")? as u16,
+        },
+    ))
+}
+
+fn save_station_to_section(area: usize, save_station: &SaveStation) -> Section {
+    let mut section = Section::new("save_station", None);
+    section
+        .push("area", hex(area, 2))
+        .push("room_pointer", hex(save_station.room_pointer as usize, 4))
+        .push("door_pointer", hex(save_station.door_pointer as usize, 4))
+        .push("door_bts", hex(save_station.door_bts as usize, 4))
+        .push(
+            "screen_x_position",
+            hex(save_station.screen_x_position as usize, 4),
+        )
+        .push(
+            "screen_y_position",
+            hex(save_station.screen_y_position as usize, 4),
+        )
+        .push(
+            "samus_y_offset",
+            hex(save_station.samus_y_offset as usize, 4),
+        )
+        .push(
+            "samus_x_offset",
+            hex(save_station.samus_x_offset as usize, 4),
+        );
+    section
+}
+
+fn save_station_from_section(section: &Section) -> Result<(usize, SaveStation), TextError> {
+    Ok((
+        section.number("area")?,
+        SaveStation {
+            room_pointer: section.number("room_pointer")? as u16,
+            door_pointer: section.number("door_pointer")? as u16,
+            door_bts: section.number("door_bts")? as u16,
+            screen_x_position: section.number("screen_x_position")? as u16,
+            screen_y_position: section.number("screen_y_position")? as u16,
+            samus_y_offset: section.number("samus_y_offset")? as u16,
+            samus_x_offset: section.number("samus_x_offset")? as u16,
+        },
+    ))
+}
+
+fn tileset_to_section(index: usize, tileset: &Tileset) -> Section {
+    let mut section = Section::new("tileset", Some(index));
+    section
+        .push("palette", hex(tileset.palette as usize, 6))
+        .push("graphic", hex(tileset.graphic as usize, 6))
+        .push("tile_table", hex(tileset.tile_table as usize, 6))
+        .push("use_cre", tileset.use_cre.to_string());
+    section
+}
+
+fn tileset_from_section(section: &Section) -> Result<(usize, Tileset), TextError> {
+    Ok((
+        section_address(section)?,
+        Tileset {
+            palette: section.number("palette")? as u32,
+            graphic: section.number("graphic")? as u32,
+            tile_table: section.number("tile_table")? as u32,
+            use_cre: section.boolean("use_cre")?,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::{
+        graphics::gfx::TileGfx,
+        super_metroid::{level_data::LevelData, tile_table::Tile},
+    };
+
+    /// Export every kind of asset into a project and import them back.
+    #[test]
+    fn export_and_import_project() {
+        let mut colors = [0; 64];
+        colors
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, c)| *c = i as u8 % 16);
+        let room = Room {
+            area: 0x01,
+            width: 0x02,
+            height: 0x01,
+            doors: 0x8916,
+            state_conditions: vec![
+                StateCondition {
+                    condition: Condition::EventSet(Event::CrittersEscaped),
+                    state_address: 0x91F8,
+                },
+                StateCondition {
+                    condition: Condition::Default,
+                    state_address: 0x91E6,
+                },
+            ],
+            ..Default::default()
+        };
+        let sm = SuperMetroid {
+            palettes: HashMap::from([(0xC2AD7C, Default::default())]),
+            graphics: HashMap::from([(
+                0xBAC629,
+                Gfx {
+                    tiles: vec![TileGfx { colors }; 32],
+                },
+            )]),
+            tile_tables: HashMap::from([(
+                0xC1B6F6,
+                vec![
+                    Tile {
+                        x_flip: true,
+                        sub_palette: 0x05,
+                        gfx_index: 0x123,
+                        ..Default::default()
+                    };
+                    4
+                ],
+            )]),
+            levels: HashMap::from([(
+                0xC2C2BB,
+                LevelData {
+                    layer1: vec![Default::default(); 4],
+                    bts: vec![0x01; 4],
+                    layer2: Some(vec![Default::default(); 4]),
+                },
+            )]),
+            rooms: HashMap::from([(0x8F91F8, room)]),
+            states: HashMap::from([
+                (
+                    0x91F8,
+                    State {
+                        level_address: 0xC2C2BB,
+                        ..Default::default()
+                    },
+                ),
+                (
+                    0x91E6,
+                    State {
+                        tileset: 0x02,
+                        ..Default::default()
+                    },
+                ),
+            ]),
+            doors: HashMap::from([(
+                0x838916,
+                Door {
+                    destination_room: 0x92FD,
+                    custom_asm: 0xB981,
+                    ..Default::default()
+                },
+            )]),
+            door_lists: HashMap::from([(0x8F8916, vec![0x8916, 0x8922])]),
+            save_stations: vec![
+                vec![SaveStation::default()],
+                vec![
+                    SaveStation {
+                        room_pointer: 0x91F8,
+                        ..Default::default()
+                    };
+                    2
+                ],
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+                vec![SaveStation::default()],
+            ],
+            cre_gfx: Gfx {
+                tiles: vec![TileGfx { colors }; 16],
+            },
+            cre_tileset: vec![
+                Tile {
+                    y_flip: true,
+                    gfx_index: 0x010,
+                    ..Default::default()
+                };
+                2
+            ],
+            tilesets: vec![Tileset {
+                palette: 0xC2AD7C,
+                graphic: 0xBAC629,
+                tile_table: 0xC1B6F6,
+                use_cre: true,
+            }],
+            ..Default::default()
+        };
+
+        let project = env::temp_dir().join("zen_export_and_import_project");
+        let _ = fs::remove_dir_all(&project);
+        export(&sm, &project).unwrap();
+        assert!(project.join(PALETTES).join("C2AD7C.png").exists());
+
+        let mut imported = SuperMetroid {
+            tilesets: vec![Tileset::default()],
+            ..Default::default()
+        };
+        import(&mut imported, &project).unwrap();
+        fs::remove_dir_all(&project).unwrap();
+
+        assert_eq!(imported.palettes, sm.palettes);
+        assert_eq!(imported.graphics, sm.graphics);
+        assert_eq!(imported.tile_tables, sm.tile_tables);
+        assert_eq!(imported.cre_gfx, sm.cre_gfx);
+        assert_eq!(imported.cre_tileset, sm.cre_tileset);
+        assert_eq!(imported.levels, sm.levels);
+        assert_eq!(imported.rooms, sm.rooms);
+        assert_eq!(imported.states, sm.states);
+        assert_eq!(imported.doors, sm.doors);
+        assert_eq!(imported.door_lists, sm.door_lists);
+        assert_eq!(imported.save_stations, sm.save_stations);
+        assert_eq!(imported.tilesets, sm.tilesets);
+    }
+
+    /// Fail to import an asset not named after its address.
+    #[test]
+    fn import_asset_with_bad_file_name() {
+        let project = env::temp_dir().join("zen_import_asset_with_bad_file_name");
+        let _ = fs::remove_dir_all(&project);
+        fs::create_dir_all(project.join(LEVELS)).unwrap();
+        fs::write(project.join(LEVELS).join("landing_site.lvl"), [0x00, 0x00]).unwrap();
+
+        let result = import(&mut SuperMetroid::default(), &project);
+        fs::remove_dir_all(&project).unwrap();
+        assert!(matches!(result, Err(ProjectError::BadFileName { .. })));
+    }
+
+    /// Write Conditions as text, with vanilla events by name, and read them back.
+    #[test]
+    fn convert_conditions_to_and_from_text() {
+        let conditions = [
+            (
+                Condition::EventSet(Event::ZebesAwake),
+                "EventSet ZebesAwake",
+            ),
+            (Condition::EventSet(Event::Other(0x13)), "EventSet $13"),
+            (Condition::BossDead(BossFlags::MINI_BOSS), "BossDead $02"),
+            (Condition::DoorPointerIs(0x8916), "DoorPointerIs $8916"),
+            (Condition::MorphBall, "MorphBall"),
+        ];
+        for (condition, text) in conditions.iter() {
+            assert_eq!(condition_to_text(condition), *text);
+            assert_eq!(condition_from_text(text), Some(*condition));
+        }
+
+        assert_eq!(
+            condition_from_text("EventSet $0D"),
+            Some(Condition::EventSet(Event::EscapeTimerSet))
+        );
+        assert_eq!(condition_from_text("EventSet ZebesAsleep"), None);
+        assert_eq!(condition_from_text("MorphBall $01"), None);
+    }
+}
@@ -0,0 +1,250 @@
+use std::fmt;
+
+/// Why a project text file could not be read.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextError {
+    /// The 'line' is neither a '[section]' header nor a 'key = value' field.
+    Syntax { line: usize },
+    /// The section starting at 'line' has no 'field'.
+    MissingField { line: usize, field: String },
+    /// The value at 'line' isn't a number, a list of numbers or a boolean.
+    BadValue { line: usize },
+}
+
+impl fmt::Display for TextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TextError::Syntax { line } => write!(f, "line {} is not a section or field", line),
+            TextError::MissingField { line, field } => {
+                write!(f, "section at line {} has no '{}'", line, field)
+            }
+            TextError::BadValue { line } => write!(f, "bad value at line {}", line),
+        }
+    }
+}
+
+impl std::error::Error for TextError {}
+
+/// A '[name $address]' header followed by its 'key = value' fields.
+/// Fields before the first header belong to a section without name.
+/// Numbers are written in hexadecimal with a '$' prefix, but decimal is also read.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Section {
+    pub name: String,
+    pub address: Option<usize>,
+    /// Line of the header, or of the first field when there is no header.
+    pub line: usize,
+    fields: Vec<Field>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Field {
+    key: String,
+    value: String,
+    line: usize,
+}
+
+/// Format 'value' as hexadecimal with at least 'digits' digits.
+pub fn hex(value: usize, digits: usize) -> String {
+    format!("${:0width$X}", value, width = digits)
+}
+
+impl Section {
+    pub fn new(name: &str, address: Option<usize>) -> Section {
+        Section {
+            name: name.to_string(),
+            address,
+            ..Default::default()
+        }
+    }
+
+    pub fn push(&mut self, key: &str, value: impl Into<String>) -> &mut Section {
+        self.fields.push(Field {
+            key: key.to_string(),
+            value: value.into(),
+            line: 0,
+        });
+        self
+    }
+
+    fn field(&self, key: &str) -> Result<&Field, TextError> {
+        self.fields
+            .iter()
+            .find(|field| field.key == key)
+            .ok_or_else(|| TextError::MissingField {
+                line: self.line,
+                field: key.to_string(),
+            })
+    }
+
+    pub fn number(&self, key: &str) -> Result<usize, TextError> {
+        let field = self.field(key)?;
+        parse_number(&field.value).ok_or(TextError::BadValue { line: field.line })
+    }
+
+    /// A number which may be written as '-', or be missing, when there is none.
+    pub fn optional_number(&self, key: &str) -> Result<Option<usize>, TextError> {
+        match self.field(key) {
+            Ok(field) if field.value == "-" => Ok(None),
+            Ok(_) => self.number(key).map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// A list of numbers separated by commas, which may be empty.
+    pub fn numbers(&self, key: &str) -> Result<Vec<usize>, TextError> {
+        let field = self.field(key)?;
+        field
+            .value
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| parse_number(value).ok_or(TextError::BadValue { line: field.line }))
+            .collect()
+    }
+
+    /// A value read by 'parse', which returns 'None' when it's bad.
+    pub fn parse<T>(&self, key: &str, parse: impl Fn(&str) -> Option<T>) -> Result<T, TextError> {
+        let field = self.field(key)?;
+        parse(&field.value).ok_or(TextError::BadValue { line: field.line })
+    }
+
+    pub fn boolean(&self, key: &str) -> Result<bool, TextError> {
+        let field = self.field(key)?;
+        field
+            .value
+            .parse()
+            .map_err(|_| TextError::BadValue { line: field.line })
+    }
+}
+
+impl fmt::Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.name.is_empty(), self.address) {
+            (true, _) => {}
+            (false, Some(address)) => writeln!(f, "[{} {}]", self.name, hex(address, 4))?,
+            (false, None) => writeln!(f, "[{}]", self.name)?,
+        }
+        for field in self.fields.iter() {
+            writeln!(f, "{} = {}", field.key, field.value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Join 'sections' into a text, with a blank line between each one.
+pub fn to_text(sections: &[Section]) -> String {
+    sections
+        .iter()
+        .map(Section::to_string)
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Split 'text' into its sections. Blank lines and lines starting with '#' are skipped.
+pub fn parse(text: &str) -> Result<Vec<Section>, TextError> {
+    let mut sections: Vec<Section> = Vec::new();
+
+    for (number, line) in text.lines().enumerate() {
+        let number = number + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let mut words = header.split_whitespace();
+            let name = words.next().ok_or(TextError::Syntax { line: number })?;
+            let address = match words.next() {
+                Some(word) => Some(parse_number(word).ok_or(TextError::BadValue { line: number })?),
+                None => None,
+            };
+            if words.next().is_some() {
+                return Err(TextError::Syntax { line: number });
+            }
+
+            sections.push(Section {
+                line: number,
+                ..Section::new(name, address)
+            });
+        } else if let Some((key, value)) = line.split_once('=') {
+            if sections.is_empty() {
+                sections.push(Section {
+                    line: number,
+                    ..Default::default()
+                });
+            }
+            sections.last_mut().unwrap().fields.push(Field {
+                key: key.trim().to_string(),
+                value: value.trim().to_string(),
+                line: number,
+            });
+        } else {
+            return Err(TextError::Syntax { line: number });
+        }
+    }
+
+    Ok(sections)
+}
+
+pub fn parse_number(value: &str) -> Option<usize> {
+    match value.strip_prefix('$') {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write sections into text and parse them back.
+    #[test]
+    fn write_and_parse_sections() {
+        let mut room = Section::new("", None);
+        room.push("area", hex(0x02, 2)).push("doors", "$8916, 12");
+        let mut state = Section::new("state", Some(0x91F8));
+        state.push("parameter", "-").push("use_cre", "true");
+
+        let text = to_text(&[room, state]);
+        assert_eq!(
+            text,
+            "area = $02\ndoors = $8916, 12\n\n[state $91F8]\nparameter = -\nuse_cre = true\n"
+        );
+
+        let sections = parse(&text).unwrap();
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].number("area").unwrap(), 0x02);
+        assert_eq!(sections[0].numbers("doors").unwrap(), [0x8916, 12]);
+        assert_eq!(sections[1].name, "state");
+        assert_eq!(sections[1].address, Some(0x91F8));
+        assert_eq!(sections[1].optional_number("parameter").unwrap(), None);
+        assert!(sections[1].boolean("use_cre").unwrap());
+    }
+
+    /// Fail to parse bad lines, and to read missing fields or bad values.
+    #[test]
+    fn parse_bad_sections() {
+        assert_eq!(
+            parse("area = $02\nwidth").unwrap_err(),
+            TextError::Syntax { line: 2 }
+        );
+        assert_eq!(
+            parse("[state $XY]").unwrap_err(),
+            TextError::BadValue { line: 1 }
+        );
+
+        let sections = parse("\n[door $8916]\nwidth = $0G").unwrap();
+        assert_eq!(
+            sections[0].number("width").unwrap_err(),
+            TextError::BadValue { line: 3 }
+        );
+        assert_eq!(
+            sections[0].number("height").unwrap_err(),
+            TextError::MissingField {
+                line: 2,
+                field: "height".to_string()
+            }
+        );
+    }
+}
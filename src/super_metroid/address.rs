@@ -4,307 +4,48 @@ pub const CRE_TILESET: usize = 0xB9A09D;
 
 pub const TILESETS: usize = 0x8FE6A2;
 
+/// Room headers, their States and Door Lists are all in this bank.
+pub const ROOMS_BANK: usize = 0x8F_0000;
+
+pub const SAVE_STATIONS_BANK: usize = 0x80_0000;
 pub const SAVE_STATIONS_LIST: usize = 0x80C4B5;
 pub const NUMBER_OF_AREAS: usize = 8;
 
-pub const SAVE_STATIONS: usize = 0x80C4C5;
-
-pub const ROOMS: &[usize] = &[
-    0x8F91F8, 0x8F92B3, 0x8F92FD, 0x8F93AA, 0x8F93D5, 0x8F93FE, 0x8F9461, 0x8F948C, 0x8F94CC,
-    0x8F94FD, 0x8F9552, 0x8F957D, 0x8F95A8, 0x8F95D4, 0x8F95FF, 0x8F962A, 0x8F965B, 0x8F968F,
-    0x8F96BA, 0x8F975C, 0x8F97B5, 0x8F9804, 0x8F9879, 0x8F98E2, 0x8F990D, 0x8F9938, 0x8F9969,
-    0x8F9994, 0x8F99BD, 0x8F99F9, 0x8F9A44, 0x8F9A90, 0x8F9AD9, 0x8F9B5B, 0x8F9B9D, 0x8F9BC8,
-    0x8F9C07, 0x8F9C35, 0x8F9C5E, 0x8F9C89, 0x8F9CB3, 0x8F9D19, 0x8F9D9C, 0x8F9DC7, 0x8F9E11,
-    0x8F9E52, 0x8F9E9F, 0x8F9F11, 0x8F9F64, 0x8F9FBA, 0x8F9FE5, 0x8FA011, 0x8FA051, 0x8FA07B,
-    0x8FA0A4, 0x8FA0D2, 0x8FA107, 0x8FA130, 0x8FA15B, 0x8FA184, 0x8FA1AD, 0x8FA1D8, 0x8FA201,
-    0x8FA22A, 0x8FA253, 0x8FA293, 0x8FA2CE, 0x8FA2F7, 0x8FA322, 0x8FA37C, 0x8FA3AE, 0x8FA3DD,
-    0x8FA408, 0x8FA447, 0x8FA471, 0x8FA4B1, 0x8FA4DA, 0x8FA521, 0x8FA56B, 0x8FA59F, 0x8FA5ED,
-    0x8FA618, 0x8FA641, 0x8FA66A, 0x8FA6A1, 0x8FA6E2, 0x8FA70B, 0x8FA734, 0x8FA75D, 0x8FA788,
-    0x8FA7B3, 0x8FA7DE, 0x8FA815, 0x8FA865, 0x8FA890, 0x8FA8B9, 0x8FA8F8, 0x8FA923, 0x8FA98D,
-    0x8FA9E5, 0x8FAA0E, 0x8FAA41, 0x8FAA82, 0x8FAAB5, 0x8FAADE, 0x8FAB07, 0x8FAB3B, 0x8FAB64,
-    0x8FAB8F, 0x8FABD2, 0x8FAC00, 0x8FAC2B, 0x8FAC5A, 0x8FAC83, 0x8FACB3, 0x8FACF0, 0x8FAD1B,
-    0x8FAD5E, 0x8FADAD, 0x8FADDE, 0x8FAE07, 0x8FAE32, 0x8FAE74, 0x8FAEB4, 0x8FAEDF, 0x8FAF14,
-    0x8FAF3F, 0x8FAF72, 0x8FAFA3, 0x8FAFCE, 0x8FAFFB, 0x8FB026, 0x8FB051, 0x8FB07A, 0x8FB0B4,
-    0x8FB0DD, 0x8FB106, 0x8FB139, 0x8FB167, 0x8FB192, 0x8FB1BB, 0x8FB1E5, 0x8FB236, 0x8FB283,
-    0x8FB2DA, 0x8FB305, 0x8FB32E, 0x8FB37A, 0x8FB3A5, 0x8FB3E1, 0x8FB40A, 0x8FB457, 0x8FB482,
-    0x8FB4AD, 0x8FB4E5, 0x8FB510, 0x8FB55A, 0x8FB585, 0x8FB5D5, 0x8FB62B, 0x8FB656, 0x8FB698,
-    0x8FB6C1, 0x8FB6EE, 0x8FB741, 0x8FC98E, 0x8FCA08, 0x8FCA52, 0x8FCAAE, 0x8FCAF6, 0x8FCB8B,
-    0x8FCBD5, 0x8FCC27, 0x8FCC6F, 0x8FCCCB, 0x8FCD13, 0x8FCD5C, 0x8FCDA8, 0x8FCDF1, 0x8FCE40,
-    0x8FCE8A, 0x8FCED2, 0x8FCEFB, 0x8FCF54, 0x8FCF80, 0x8FCFC9, 0x8FD017, 0x8FD055, 0x8FD08A,
-    0x8FD0B9, 0x8FD104, 0x8FD13B, 0x8FD16D, 0x8FD1A3, 0x8FD1DD, 0x8FD21C, 0x8FD252, 0x8FD27E,
-    0x8FD2AA, 0x8FD2D9, 0x8FD30B, 0x8FD340, 0x8FD387, 0x8FD3B6, 0x8FD3DF, 0x8FD408, 0x8FD433,
-    0x8FD461, 0x8FD48E, 0x8FD4C2, 0x8FD4EF, 0x8FD51E, 0x8FD54D, 0x8FD57A, 0x8FD5A7, 0x8FD5EC,
-    0x8FD617, 0x8FD646, 0x8FD69A, 0x8FD6D0, 0x8FD6FD, 0x8FD72A, 0x8FD765, 0x8FD78F, 0x8FD7E4,
-    0x8FD81A, 0x8FD845, 0x8FD86E, 0x8FD898, 0x8FD8C5, 0x8FD913, 0x8FD95E, 0x8FD9AA, 0x8FD9D4,
-    0x8FD9FE, 0x8FDA2B, 0x8FDA60, 0x8FDAAE, 0x8FDAE1, 0x8FDB31, 0x8FDB7D, 0x8FDBCD, 0x8FDC19,
-    0x8FDC65, 0x8FDCB1, 0x8FDCFF, 0x8FDD2E, 0x8FDD58, 0x8FDDC4, 0x8FDDF3, 0x8FDE23, 0x8FDE4D,
-    0x8FDE7A, 0x8FDEA7, 0x8FDEDE, 0x8FDF1B, 0x8FDF45, 0x8FDF8D, 0x8FDFD7, 0x8FE021, 0x8FE06B,
-    0x8FE0B5,
+/// Unused regions of the vanilla ROM, as inclusive LoRom ranges.
+pub const FREE_SPACE: &[(usize, usize)] = &[
+    (0x80CD8E, 0x80FFBF),
+    (0x83AD66, 0x83FFFF),
+    (0x8FE99B, 0x8FFFFF),
+    (0xA1EBD1, 0xA1FFFF),
+    (0xB4F4B8, 0xB4FFFF),
+    (0xB88000, 0xB8FFFF),
+    (0xCEB22E, 0xCEFFFF),
+    (0xDFD4DF, 0xDFFFFF),
 ];
 
-pub const DOORS: (usize, usize) = (379, 0x8388FE);
+/// Rooms where a game starts besides the Save Stations ones: Ceres Elevator and Landing Site.
+pub const ENTRANCES: &[u16] = &[0xDF45, 0x91F8];
+
+/// Doors are all in this bank, referenced by Door Lists.
+pub const DOORS_BANK: usize = 0x83_0000;
+
+/// Enemy Populations and Enemy Sets of every State are in these banks.
+pub const ENEMY_POPULATIONS_BANK: usize = 0xA1_0000;
+pub const ENEMY_SETS_BANK: usize = 0xB4_0000;
+
+/// Enemy Species headers, pointed to by enemies of the populations and sets.
+pub const ENEMY_SPECIES_BANK: usize = 0xA0_0000;
+/// Names of the Enemy Species, pointed to by their headers.
+pub const ENEMY_NAMES_BANK: usize = 0xB4_0000;
+
+/// FX lists of every State, in the same bank as Doors.
+pub const FX_BANK: usize = 0x83_0000;
 
-pub const DOORS_LIST: &[(usize, usize)] = &[
-    (4, 0x8F927B),
-    (2, 0x8F92F9),
-    (7, 0x8F9362),
-    (1, 0x8F93D1),
-    (1, 0x8F93FC),
-    (6, 0x8F9425),
-    (2, 0x8F9488),
-    (3, 0x8F94B3),
-    (3, 0x8F94F3),
-    (2, 0x8F9524),
-    (2, 0x8F9579),
-    (2, 0x8F95A4),
-    (2, 0x8F95CF),
-    (2, 0x8F95FB),
-    (2, 0x8F9626),
-    (3, 0x8F9651),
-    (2, 0x8F9682),
-    (2, 0x8F96B6),
-    (5, 0x8F971F),
-    (2, 0x8F97A1),
-    (3, 0x8F97FA),
-    (1, 0x8F9869),
-    (2, 0x8F98DE),
-    (2, 0x8F9909),
-    (2, 0x8F9934),
-    (3, 0x8F995F),
-    (2, 0x8F9990),
-    (1, 0x8F99BB),
-    (4, 0x8F99E4),
-    (2, 0x8F9A20),
-    (2, 0x8F9A8A),
-    (1, 0x8F9AD6),
-    (11, 0x8F9B00),
-    (2, 0x8F9B82),
-    (2, 0x8F9BC4),
-    (2, 0x8F9BEF),
-    (1, 0x8F9C2E),
-    (1, 0x8F9C5C),
-    (2, 0x8F9C85),
-    (1, 0x8F9CB0),
-    (3, 0x8F9CDA),
-    (9, 0x8F9D40),
-    (2, 0x8F9DC3),
-    (2, 0x8F9E0D),
-    (2, 0x8F9E38),
-    (3, 0x8F9E79),
-    (4, 0x8F9EE5),
-    (3, 0x8F9F57),
-    (2, 0x8F9FAA),
-    (2, 0x8F9FE1),
-    (2, 0x8FA00C),
-    (4, 0x8FA038),
-    (1, 0x8FA078),
-    (1, 0x8FA0A2),
-    (2, 0x8FA0CB),
-    (2, 0x8FA0F9),
-    (1, 0x8FA12E),
-    (2, 0x8FA157),
-    (1, 0x8FA182),
-    (1, 0x8FA1AB),
-    (2, 0x8FA1D4),
-    (1, 0x8FA1FF),
-    (1, 0x8FA228),
-    (1, 0x8FA251),
-    (5, 0x8FA27A),
-    (2, 0x8FA2BA),
-    (1, 0x8FA2F5),
-    (2, 0x8FA31E),
-    (7, 0x8FA349),
-    (1, 0x8FA3A3),
-    (1, 0x8FA3D5),
-    (2, 0x8FA404),
-    (3, 0x8FA42F),
-    (1, 0x8FA46E),
-    (3, 0x8FA498),
-    (1, 0x8FA4D8),
-    (3, 0x8FA501),
-    (2, 0x8FA567),
-    (3, 0x8FA592),
-    (2, 0x8FA5E5),
-    (2, 0x8FA614),
-    (1, 0x8FA63F),
-    (1, 0x8FA668),
-    (3, 0x8FA691),
-    (4, 0x8FA6C8),
-    (1, 0x8FA709),
-    (1, 0x8FA732),
-    (1, 0x8FA75B),
-    (2, 0x8FA784),
-    (2, 0x8FA7AF),
-    (2, 0x8FA7DA),
-    (8, 0x8FA805),
-    (4, 0x8FA83C),
-    (2, 0x8FA88C),
-    (1, 0x8FA8B7),
-    (3, 0x8FA8E0),
-    (2, 0x8FA91F),
-    (5, 0x8FA94A),
-    (2, 0x8FA9D3),
-    (1, 0x8FAA0C),
-    (2, 0x8FAA35),
-    (2, 0x8FAA68),
-    (4, 0x8FAAA9),
-    (1, 0x8FAADC),
-    (1, 0x8FAB05),
-    (4, 0x8FAB2E),
-    (1, 0x8FAB62),
-    (2, 0x8FAB8B),
-    (2, 0x8FABB6),
-    (2, 0x8FABF9),
-    (2, 0x8FAC27),
-    (2, 0x8FAC52),
-    (1, 0x8FAC81),
-    (2, 0x8FACAA),
-    (7, 0x8FACDA),
-    (2, 0x8FAD17),
-    (1, 0x8FAD5C),
-    (5, 0x8FAD85),
-    (3, 0x8FADD4),
-    (1, 0x8FAE05),
-    (2, 0x8FAE2E),
-    (2, 0x8FAE59),
-    (4, 0x8FAE9B),
-    (2, 0x8FAEDB),
-    (3, 0x8FAF06),
-    (2, 0x8FAF3B),
-    (4, 0x8FAF66),
-    (3, 0x8FAF99),
-    (2, 0x8FAFCA),
-    (3, 0x8FAFF5),
-    (2, 0x8FB022),
-    (2, 0x8FB04D),
-    (1, 0x8FB078),
-    (2, 0x8FB0A1),
-    (1, 0x8FB0DB),
-    (1, 0x8FB104),
-    (2, 0x8FB12D),
-    (2, 0x8FB160),
-    (2, 0x8FB18E),
-    (1, 0x8FB1B9),
-    (1, 0x8FB1E2),
-    (2, 0x8FB20C),
-    (4, 0x8FB25D),
-    (2, 0x8FB2C9),
-    (2, 0x8FB301),
-    (1, 0x8FB32C),
-    (2, 0x8FB374),
-    (2, 0x8FB3A1),
-    (5, 0x8FB3CC),
-    (1, 0x8FB408),
-    (2, 0x8FB431),
-    (2, 0x8FB47E),
-    (2, 0x8FB4A9),
-    (3, 0x8FB4D4),
-    (2, 0x8FB50C),
-    (3, 0x8FB537),
-    (3, 0x8FB581),
-    (4, 0x8FB5AC),
-    (2, 0x8FB5FC),
-    (2, 0x8FB652),
-    (2, 0x8FB67D),
-    (1, 0x8FB6BF),
-    (3, 0x8FB6E8),
-    (3, 0x8FB715),
-    (1, 0x8FB768),
-    (3, 0x8FC9D4),
-    (2, 0x8FCA4E),
-    (3, 0x8FCA98),
-    (1, 0x8FCAF4),
-    (7, 0x8FCB3C),
-    (2, 0x8FCBD1),
-    (3, 0x8FCC1B),
-    (1, 0x8FCC6D),
-    (3, 0x8FCCB5),
-    (1, 0x8FCD11),
-    (1, 0x8FCD59),
-    (2, 0x8FCDA2),
-    (1, 0x8FCDEE),
-    (1, 0x8FCE37),
-    (2, 0x8FCE86),
-    (1, 0x8FCED0),
-    (1, 0x8FCEF9),
-    (4, 0x8FCF41),
-    (2, 0x8FCF7B),
-    (3, 0x8FCFA7),
-    (5, 0x8FCFF0),
-    (4, 0x8FD03E),
-    (1, 0x8FD07C),
-    (2, 0x8FD0B1),
-    (6, 0x8FD0E0),
-    (2, 0x8FD12B),
-    (1, 0x8FD162),
-    (2, 0x8FD194),
-    (3, 0x8FD1CA),
-    (3, 0x8FD204),
-    (4, 0x8FD243),
-    (2, 0x8FD279),
-    (2, 0x8FD2A5),
-    (1, 0x8FD2D1),
-    (2, 0x8FD300),
-    (4, 0x8FD332),
-    (4, 0x8FD367),
-    (2, 0x8FD3AE),
-    (1, 0x8FD3DD),
-    (1, 0x8FD406),
-    (2, 0x8FD42F),
-    (3, 0x8FD45A),
-    (3, 0x8FD488),
-    (3, 0x8FD4B5),
-    (3, 0x8FD4E9),
-    (2, 0x8FD516),
-    (2, 0x8FD545),
-    (2, 0x8FD574),
-    (2, 0x8FD5A1),
-    (6, 0x8FD5CE),
-    (2, 0x8FD613),
-    (2, 0x8FD63E),
-    (4, 0x8FD66D),
-    (2, 0x8FD6C1),
-    (1, 0x8FD6F7),
-    (1, 0x8FD724),
-    (3, 0x8FD751),
-    (1, 0x8FD78C),
-    (2, 0x8FD7D5),
-    (4, 0x8FD80B),
-    (2, 0x8FD841),
-    (1, 0x8FD86C),
-    (1, 0x8FD895),
-    (2, 0x8FD8BF),
-    (2, 0x8FD90B),
-    (4, 0x8FD93A),
-    (2, 0x8FD9A4),
-    (1, 0x8FD9D1),
-    (1, 0x8FD9FB),
-    (2, 0x8FDA25),
-    (2, 0x8FDA52),
-    (2, 0x8FDAA6),
-    (4, 0x8FDAD5),
-    (2, 0x8FDB27),
-    (2, 0x8FDB77),
-    (2, 0x8FDBC3),
-    (2, 0x8FDC13),
-    (2, 0x8FDC5F),
-    (2, 0x8FDCAB),
-    (2, 0x8FDCF7),
-    (3, 0x8FDD26),
-    (1, 0x8FDD55),
-    (2, 0x8FDDBC),
-    (2, 0x8FDDEB),
-    (3, 0x8FDE1A),
-    (1, 0x8FDE4A),
-    (2, 0x8FDE74),
-    (2, 0x8FDEA1),
-    (2, 0x8FDECE),
-    (2, 0x8FDF05),
-    (1, 0x8FDF42),
-    (1, 0x8FDF8B),
-    (2, 0x8FDFD3),
-    (2, 0x8FE01D),
-    (2, 0x8FE067),
-    (2, 0x8FE0B1),
-    (1, 0x8FE0FB),
+/// Banks of the assets only pointed to by 16 bits, which can't be written anywhere else.
+pub const RESTRICTED_BANKS: [usize; 5] = [
+    SAVE_STATIONS_BANK,
+    DOORS_BANK,
+    ROOMS_BANK,
+    ENEMY_POPULATIONS_BANK,
+    ENEMY_SETS_BANK,
 ];
@@ -1,6 +1,8 @@
 /// Door format reference: https://wiki.metroidconstruction.com/doku.php?id=super:technical_information:data_structures#door_header
 #[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Door {
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
     pub destination_room: u16, // Destination room header pointer (bank $8F)
     pub elevator_property: u8,
     pub orientation: u8,
@@ -9,7 +11,26 @@ pub struct Door {
     pub x_high_byte: u8,
     pub y_high_byte: u8,
     pub samus_door_distance: u16, // Distance from door to spawn Samus
-    pub custom_asm: u16,          // Custom door ASM to execute (bank $8F)
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub custom_asm: u16, // Custom door ASM to execute (bank $8F)
+}
+
+impl Door {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut output = Vec::new();
+        output.extend(self.destination_room.to_le_bytes());
+        output.extend([
+            self.elevator_property,
+            self.orientation,
+            self.x_low_byte,
+            self.y_low_byte,
+            self.x_high_byte,
+            self.y_high_byte,
+        ]);
+        output.extend(self.samus_door_distance.to_le_bytes());
+        output.extend(self.custom_asm.to_le_bytes());
+        output
+    }
 }
 
 pub const DOOR_BYTE_SIZE: usize = 12;
@@ -37,6 +58,7 @@ mod tests {
     use super::*;
 
     /// Load 3 doors from bytes.
+    /// Convert doors into bytes.
     #[test]
     fn load_doors_from_bytes() {
         #[rustfmt::skip]
@@ -81,5 +103,11 @@ mod tests {
             },
         ];
         assert_eq!(doors, expected_doors);
+
+        let doors_to_bytes = doors.iter().fold(Vec::new(), |mut acc, door| {
+            acc.extend(door.to_bytes());
+            acc
+        });
+        assert_eq!(doors_to_bytes, data);
     }
 }
@@ -8,22 +8,27 @@ pub fn load_bytes(number_of_doors: usize, source: &[u8]) -> DoorList {
         .collect()
 }
 
-// Workaround for finding how many doors a Room have.
-// fn get_number_of_doors_on(level_data: &LevelData) -> usize {
-//     let mut number_of_doors = 0;
-//     for (block, bts) in level_data.layer1.iter().zip(level_data.bts.iter()) {
-//         if block.block_type == 0x9 {
-//             number_of_doors = number_of_doors.max(*bts + 1);
-//         }
-//     }
-//     number_of_doors as usize
-// }
+pub fn to_bytes(door_list: &DoorList) -> Vec<u8> {
+    door_list
+        .iter()
+        .flat_map(|door_pointer| door_pointer.to_le_bytes())
+        .collect()
+}
+
+/// Door Lists have no terminator, so count door pointers until 'is_door' rejects one.
+pub fn count_doors(source: &[u8], is_door: impl Fn(u16) -> bool) -> usize {
+    source
+        .chunks_exact(2)
+        .take_while(|bytes| is_door(u16::from_le_bytes([bytes[0], bytes[1]])))
+        .count()
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
     /// Load a door list from bytes with 3 door pointers, each as a 2 byte address in Little Endian.
+    /// Convert a door list into bytes.
     #[test]
     fn load_door_list_from_bytes() {
         let data = [0x34, 0x12, 0x78, 0x56, 0xBC, 0x9A];
@@ -31,5 +36,14 @@ mod tests {
 
         let expected_door_list = [0x1234, 0x5678, 0x9ABC];
         assert_eq!(door_list, expected_door_list);
+        assert_eq!(to_bytes(&door_list), data);
+    }
+
+    /// Count door pointers until an invalid one.
+    #[test]
+    fn count_doors_from_bytes() {
+        let data = [0x34, 0x92, 0x78, 0x96, 0x00, 0x01, 0xBC, 0x9A];
+        assert_eq!(count_doors(&data, |pointer| pointer >= 0x8000), 2);
+        assert_eq!(count_doors(&data[..3], |pointer| pointer >= 0x8000), 1);
     }
 }
@@ -0,0 +1,212 @@
+/// An enemy placed in a Room, in the enemy population of bank $A1.
+/// Format reference: https://wiki.metroidconstruction.com/doku.php?id=super:technical_information:data_structures#enemy_population
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Enemy {
+    /// Pointer to the species header in bank $A0.
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub species: u16,
+    /// Position in pixels.
+    pub x: u16,
+    pub y: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub init_param: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub properties: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub extra_properties: u16,
+    /// Parameters of the species AI, often its speed.
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub speed: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub speed_2: u16,
+}
+
+pub const ENEMY_BYTE_SIZE: usize = 16;
+
+/// Every enemy of a State, ended in ROM by $FFFF and the kill count.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EnemyPopulation {
+    pub enemies: Vec<Enemy>,
+    /// Number of enemies to kill to clear the Room, opening its grey doors.
+    pub kill_count: u8,
+}
+
+/// A species whose graphics are loaded for a State, in the enemy set of bank $B4.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EnemySetEntry {
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub species: u16,
+    /// Sprite palette slot the species is loaded into.
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub palette: u16,
+}
+
+/// Every species used by a State, ended in ROM by $FFFF.
+pub type EnemySet = Vec<EnemySetEntry>;
+
+pub const ENEMY_SET_ENTRY_BYTE_SIZE: usize = 4;
+
+const TERMINATOR: u16 = 0xFFFF;
+
+fn words(source: &[u8]) -> impl Iterator<Item = u16> + '_ {
+    source
+        .chunks_exact(2)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+impl Enemy {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [
+            self.species,
+            self.x,
+            self.y,
+            self.init_param,
+            self.properties,
+            self.extra_properties,
+            self.speed,
+            self.speed_2,
+        ]
+        .iter()
+        .flat_map(|word| word.to_le_bytes())
+        .collect()
+    }
+}
+
+impl EnemyPopulation {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut output: Vec<u8> = self.enemies.iter().flat_map(Enemy::to_bytes).collect();
+        output.extend(TERMINATOR.to_le_bytes());
+        output.push(self.kill_count);
+        output
+    }
+}
+
+/// Load enemies until the terminator followed by the kill count.
+/// A population cut short by the end of 'source' has a kill count of 0.
+pub fn load_population(source: &[u8]) -> EnemyPopulation {
+    let enemies: Vec<Enemy> = source
+        .chunks(ENEMY_BYTE_SIZE)
+        .take_while(|bytes| {
+            bytes.len() == ENEMY_BYTE_SIZE && bytes[..2] != TERMINATOR.to_le_bytes()
+        })
+        .map(|bytes| {
+            let words: Vec<u16> = words(bytes).collect();
+            Enemy {
+                species: words[0],
+                x: words[1],
+                y: words[2],
+                init_param: words[3],
+                properties: words[4],
+                extra_properties: words[5],
+                speed: words[6],
+                speed_2: words[7],
+            }
+        })
+        .collect();
+
+    let terminator = enemies.len() * ENEMY_BYTE_SIZE;
+    let kill_count = match source.get(terminator..terminator + 3) {
+        Some([0xFF, 0xFF, kill_count]) => *kill_count,
+        _ => 0,
+    };
+    EnemyPopulation {
+        enemies,
+        kill_count,
+    }
+}
+
+/// Load species until the terminator, or the end of 'source'.
+pub fn load_set(source: &[u8]) -> EnemySet {
+    source
+        .chunks_exact(ENEMY_SET_ENTRY_BYTE_SIZE)
+        .map(|bytes| EnemySetEntry {
+            species: u16::from_le_bytes([bytes[0], bytes[1]]),
+            palette: u16::from_le_bytes([bytes[2], bytes[3]]),
+        })
+        .take_while(|entry| entry.species != TERMINATOR)
+        .collect()
+}
+
+pub fn set_to_bytes(enemy_set: &[EnemySetEntry]) -> Vec<u8> {
+    enemy_set
+        .iter()
+        .flat_map(|entry| [entry.species.to_le_bytes(), entry.palette.to_le_bytes()])
+        .flatten()
+        .chain(TERMINATOR.to_le_bytes())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Load an enemy population from bytes and convert it back.
+    #[test]
+    fn load_enemy_population_from_bytes() {
+        #[rustfmt::skip]
+        let data = [
+            0x7F, 0xD0, // species
+            0x80, 0x02, // x
+            0x48, 0x00, // y
+            0x00, 0x00, // init_param
+            0x00, 0x20, // properties
+            0x00, 0x00, // extra_properties
+            0x01, 0x00, // speed
+            0x10, 0x00, // speed_2
+            0xFF, 0xFF, // Terminator
+            0x01,       // kill_count
+            0xAA,
+        ];
+
+        let population = load_population(&data);
+        assert_eq!(
+            population,
+            EnemyPopulation {
+                enemies: vec![Enemy {
+                    species: 0xD07F,
+                    x: 0x0280,
+                    y: 0x0048,
+                    init_param: 0x0000,
+                    properties: 0x2000,
+                    extra_properties: 0x0000,
+                    speed: 0x0001,
+                    speed_2: 0x0010,
+                }],
+                kill_count: 0x01,
+            }
+        );
+        assert_eq!(population.to_bytes(), data[..data.len() - 1]);
+        assert_eq!(load_population(&data[..8]), EnemyPopulation::default());
+    }
+
+    /// Load an enemy set from bytes and convert it back.
+    #[test]
+    fn load_enemy_set_from_bytes() {
+        #[rustfmt::skip]
+        let data = [
+            0x7F, 0xD0, 0x01, 0x00, // species, palette
+            0xFF, 0xD0, 0x02, 0x00, // species, palette
+            0xFF, 0xFF,             // Terminator
+            0xAA,
+        ];
+
+        let enemy_set = load_set(&data);
+        assert_eq!(
+            enemy_set,
+            [
+                EnemySetEntry {
+                    species: 0xD07F,
+                    palette: 0x0001,
+                },
+                EnemySetEntry {
+                    species: 0xD0FF,
+                    palette: 0x0002,
+                },
+            ]
+        );
+        assert_eq!(set_to_bytes(&enemy_set), data[..data.len() - 1]);
+    }
+}
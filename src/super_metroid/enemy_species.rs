@@ -0,0 +1,281 @@
+use super::progress::Boss;
+
+/// Header of an enemy species in bank $A0, shared by every enemy of the species.
+/// Format reference: https://wiki.metroidconstruction.com/doku.php?id=super:technical_information:data_structures#enemy_header
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EnemySpecies {
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub tile_data_size: u16,
+    /// Pointer to the palette in the AI bank.
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub palette: u16,
+    pub health: u16,
+    /// Damage done to Samus on contact.
+    pub damage: u16,
+    /// Hitbox radius in pixels.
+    pub width: u16,
+    pub height: u16,
+    /// Bank of the AI routines and of the palette.
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub ai_bank: u8,
+    /// Frames the enemy flashes when hurt.
+    pub hurt_time: u8,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub cry: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub boss_value: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub init_ai: u16,
+    pub number_of_parts: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub unknown_16: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub main_ai: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub grapple_ai: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub hurt_ai: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub frozen_ai: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub time_is_frozen_ai: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub death_animation: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub unknown_24: u32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub power_bomb_reaction: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub unknown_2a: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub unknown_2c: u32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub enemy_touch: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub enemy_shot: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub unknown_34: u16,
+    /// Long pointer to the uncompressed tiles of the species.
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub tile_data: u32, // Only three bytes are used (u24).
+    pub layer: u8,
+    /// Pointers to tables in bank $B4, and to the name shown by the enemy debugger.
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub drop_chances: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub vulnerabilities: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub name: u16,
+}
+
+pub const ENEMY_SPECIES_BYTE_SIZE: usize = 64;
+
+/// Names in bank $B4 are this many ASCII characters, padded with spaces.
+pub const NAME_SIZE: usize = 10;
+
+/// Bosses of the vanilla game by their pointer in bank $A0, named for when the ROM has no names.
+pub const KNOWN_SPECIES: &[(u16, &str)] = &[
+    (0xDDBF, "Crocomire"),
+    (0xDE3F, "Draygon"),
+    (0xDF3F, "Spore Spawn"),
+    (0xE0FF, "Ceres Ridley"),
+    (0xE13F, "Ridley"),
+    (0xE2BF, "Kraid"),
+    (0xE4BF, "Phantoon"),
+    (0xEC7F, "Mother Brain"),
+    (0xEEFF, "Bomb Torizo"),
+    (0xEF7F, "Golden Torizo"),
+    (0xF293, "Botwoon"),
+];
+
+pub fn species_name(species: u16) -> Option<&'static str> {
+    KNOWN_SPECIES
+        .iter()
+        .find(|(pointer, _)| *pointer == species)
+        .map(|(_, name)| *name)
+}
+
+/// Name pointed to by the 'name' of a species, without its padding.
+/// None when it's cut short, blank, or has other characters than printable ASCII.
+pub fn name_from_bytes(source: &[u8]) -> Option<String> {
+    let name = source.get(..NAME_SIZE)?;
+    if !name.iter().all(|c| c.is_ascii_graphic() || *c == b' ') {
+        return None;
+    }
+    let name = String::from_utf8_lossy(name).trim_end().to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+/// Pointer in bank $A0 to the species of 'boss'.
+pub fn boss_species(boss: Boss) -> u16 {
+    match boss {
+        Boss::BombTorizo => 0xEEFF,
+        Boss::Kraid => 0xE2BF,
+        Boss::SporeSpawn => 0xDF3F,
+        Boss::Ridley => 0xE13F,
+        Boss::Crocomire => 0xDDBF,
+        Boss::GoldenTorizo => 0xEF7F,
+        Boss::Phantoon => 0xE4BF,
+        Boss::Draygon => 0xDE3F,
+        Boss::Botwoon => 0xF293,
+        Boss::MotherBrain => 0xEC7F,
+    }
+}
+
+#[rustfmt::skip]
+pub fn load_bytes(source: &[u8]) -> EnemySpecies {
+    let word = |i: usize| u16::from_le_bytes([source[i], source[i + 1]]);
+    let long = |i: usize| u32::from_le_bytes([source[i], source[i + 1], source[i + 2], source[i + 3]]);
+
+    EnemySpecies {
+        tile_data_size:      word(0x00),
+        palette:             word(0x02),
+        health:              word(0x04),
+        damage:              word(0x06),
+        width:               word(0x08),
+        height:              word(0x0A),
+        ai_bank:             source[0x0C],
+        hurt_time:           source[0x0D],
+        cry:                 word(0x0E),
+        boss_value:          word(0x10),
+        init_ai:             word(0x12),
+        number_of_parts:     word(0x14),
+        unknown_16:          word(0x16),
+        main_ai:             word(0x18),
+        grapple_ai:          word(0x1A),
+        hurt_ai:             word(0x1C),
+        frozen_ai:           word(0x1E),
+        time_is_frozen_ai:   word(0x20),
+        death_animation:     word(0x22),
+        unknown_24:          long(0x24),
+        power_bomb_reaction: word(0x28),
+        unknown_2a:          word(0x2A),
+        unknown_2c:          long(0x2C),
+        enemy_touch:         word(0x30),
+        enemy_shot:          word(0x32),
+        unknown_34:          word(0x34),
+        tile_data:           u32::from_le_bytes([source[0x36], source[0x37], source[0x38], 0]),
+        layer:               source[0x39],
+        drop_chances:        word(0x3A),
+        vulnerabilities:     word(0x3C),
+        name:                word(0x3E),
+    }
+}
+
+impl EnemySpecies {
+    pub fn to_bytes(&self) -> [u8; ENEMY_SPECIES_BYTE_SIZE] {
+        let mut output = [0; ENEMY_SPECIES_BYTE_SIZE];
+        let mut write = |i: usize, bytes: &[u8]| output[i..i + bytes.len()].copy_from_slice(bytes);
+
+        write(0x00, &self.tile_data_size.to_le_bytes());
+        write(0x02, &self.palette.to_le_bytes());
+        write(0x04, &self.health.to_le_bytes());
+        write(0x06, &self.damage.to_le_bytes());
+        write(0x08, &self.width.to_le_bytes());
+        write(0x0A, &self.height.to_le_bytes());
+        write(0x0C, &[self.ai_bank, self.hurt_time]);
+        write(0x0E, &self.cry.to_le_bytes());
+        write(0x10, &self.boss_value.to_le_bytes());
+        write(0x12, &self.init_ai.to_le_bytes());
+        write(0x14, &self.number_of_parts.to_le_bytes());
+        write(0x16, &self.unknown_16.to_le_bytes());
+        write(0x18, &self.main_ai.to_le_bytes());
+        write(0x1A, &self.grapple_ai.to_le_bytes());
+        write(0x1C, &self.hurt_ai.to_le_bytes());
+        write(0x1E, &self.frozen_ai.to_le_bytes());
+        write(0x20, &self.time_is_frozen_ai.to_le_bytes());
+        write(0x22, &self.death_animation.to_le_bytes());
+        write(0x24, &self.unknown_24.to_le_bytes());
+        write(0x28, &self.power_bomb_reaction.to_le_bytes());
+        write(0x2A, &self.unknown_2a.to_le_bytes());
+        write(0x2C, &self.unknown_2c.to_le_bytes());
+        write(0x30, &self.enemy_touch.to_le_bytes());
+        write(0x32, &self.enemy_shot.to_le_bytes());
+        write(0x34, &self.unknown_34.to_le_bytes());
+        write(0x36, &self.tile_data.to_le_bytes()[..3]);
+        write(0x39, &[self.layer]);
+        write(0x3A, &self.drop_chances.to_le_bytes());
+        write(0x3C, &self.vulnerabilities.to_le_bytes());
+        write(0x3E, &self.name.to_le_bytes());
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Load an enemy species header from bytes and convert it back.
+    #[test]
+    fn load_enemy_species_from_bytes() {
+        #[rustfmt::skip]
+        let data = [
+            0x00, 0x04, // tile_data_size
+            0x87, 0x86, // palette
+            0xE8, 0x03, // health
+            0x14, 0x00, // damage
+            0x08, 0x00, // width
+            0x10, 0x00, // height
+            0xA7,       // ai_bank
+            0x08,       // hurt_time
+            0x54, 0x00, // cry
+            0x00, 0x00, // boss_value
+            0x2F, 0xAF, // init_ai
+            0x01, 0x00, // number_of_parts
+            0x00, 0x00, // unknown_16
+            0x8B, 0xAF, // main_ai
+            0x00, 0x80, // grapple_ai
+            0x04, 0x80, // hurt_ai
+            0x41, 0x80, // frozen_ai
+            0x00, 0x00, // time_is_frozen_ai
+            0x02, 0x00, // death_animation
+            0x00, 0x00, 0x00, 0x00, // unknown_24
+            0x00, 0x00, // power_bomb_reaction
+            0x00, 0x00, // unknown_2a
+            0x00, 0x00, 0x00, 0x00, // unknown_2c
+            0x00, 0x00, // enemy_touch
+            0x00, 0x00, // enemy_shot
+            0x00, 0x00, // unknown_34
+            0x00, 0x80, 0xAB, // tile_data
+            0x05,       // layer
+            0x00, 0xF2, // drop_chances
+            0x4C, 0xEC, // vulnerabilities
+            0x3E, 0xE1, // name
+        ];
+
+        let species = load_bytes(&data);
+        assert_eq!(species.health, 1000);
+        assert_eq!(species.damage, 20);
+        assert_eq!(species.ai_bank, 0xA7);
+        assert_eq!(species.tile_data, 0xAB8000);
+        assert_eq!(species.layer, 0x05);
+        assert_eq!(species.drop_chances, 0xF200);
+        assert_eq!(species.name, 0xE13E);
+        assert_eq!(species.to_bytes(), data);
+    }
+
+    /// Name known species, and find the species of each boss.
+    #[test]
+    fn name_known_species() {
+        assert_eq!(species_name(0xE2BF), Some("Kraid"));
+        assert_eq!(species_name(0xD07F), None);
+        assert_eq!(species_name(boss_species(Boss::Phantoon)), Some("Phantoon"));
+        for boss in Boss::ALL.iter() {
+            assert!(species_name(boss_species(*boss)).is_some());
+        }
+    }
+
+    /// Read the name of a species without its padding, and reject one that isn't text.
+    #[test]
+    fn read_species_name_from_bytes() {
+        assert_eq!(name_from_bytes(b"KRAID     \x00").as_deref(), Some("KRAID"));
+        assert_eq!(
+            name_from_bytes(b"SPORE SPAWN").as_deref(),
+            Some("SPORE SPAW")
+        );
+        assert_eq!(name_from_bytes(b"          "), None);
+        assert_eq!(name_from_bytes(b"ZEELA\x00\x00\x00\x00\x00"), None);
+        assert_eq!(name_from_bytes(b"ZEELA"), None);
+    }
+}
@@ -0,0 +1,242 @@
+/// Effects of a Room, like liquids and weather, in the FX entries of bank $83.
+/// Format reference: https://wiki.metroidconstruction.com/doku.php?id=super:technical_information:data_structures#fx
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fx {
+    /// Pointer of the Door Samus must come from to use this entry, 0 for any Door.
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub door: u16,
+    /// Height in pixels of the liquid surface.
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub base_y: u16,
+    /// Height the liquid moves to, at 'y_velocity' after 'timer' frames.
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub target_y: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub y_velocity: u16,
+    pub timer: u8,
+    pub fx_type: FxType,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub default_layer_blend: u8,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub layer_3_blend: u8,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub liquid_options: u8,
+    /// Bitmask of the palette effects, like glowing lights.
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub palette_fx: u8,
+    /// Bitmask of the animated tiles, like moving spikes.
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub animated_tiles: u8,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub palette_blend: u8,
+}
+
+pub const FX_BYTE_SIZE: usize = 16;
+
+/// Entries of a State, checked in order for the Door Samus came from.
+/// In ROM they end with an entry for any Door, or else with $FFFF for no effects.
+pub type FxList = Vec<Fx>;
+
+const TERMINATOR: u16 = 0xFFFF;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FxType {
+    #[default]
+    None,
+    Lava,
+    Acid,
+    Water,
+    Spores,
+    Rain,
+    Fog,
+    ScrollingSky,
+    Fireflea,
+    TourianEntranceStatue,
+    CeresRidley,
+    CeresElevator,
+    Haze,
+    /// A type not used by the vanilla game.
+    Other(u8),
+}
+
+/// Every known type along with its value in ROM.
+const FX_TYPES: [(FxType, u8); 13] = [
+    (FxType::None, 0x00),
+    (FxType::Lava, 0x02),
+    (FxType::Acid, 0x04),
+    (FxType::Water, 0x06),
+    (FxType::Spores, 0x08),
+    (FxType::Rain, 0x0A),
+    (FxType::Fog, 0x0C),
+    (FxType::ScrollingSky, 0x20),
+    (FxType::Fireflea, 0x24),
+    (FxType::TourianEntranceStatue, 0x26),
+    (FxType::CeresRidley, 0x28),
+    (FxType::CeresElevator, 0x2A),
+    (FxType::Haze, 0x2C),
+];
+
+impl FxType {
+    pub fn to_byte(self) -> u8 {
+        match self {
+            FxType::Other(value) => value,
+            fx_type => FX_TYPES
+                .iter()
+                .find(|(known, _)| *known == fx_type)
+                .map(|(_, value)| *value)
+                .unwrap(),
+        }
+    }
+
+    pub fn from_byte(value: u8) -> FxType {
+        FX_TYPES
+            .iter()
+            .find(|(_, known)| *known == value)
+            .map(|(fx_type, _)| *fx_type)
+            .unwrap_or(FxType::Other(value))
+    }
+
+    pub fn is_liquid(self) -> bool {
+        matches!(self, FxType::Lava | FxType::Acid | FxType::Water)
+    }
+}
+
+#[rustfmt::skip]
+fn fx_from_bytes(source: &[u8]) -> Fx {
+    let word = |i: usize| u16::from_le_bytes([source[i], source[i + 1]]);
+
+    Fx {
+        door:                word(0x00),
+        base_y:              word(0x02),
+        target_y:            word(0x04),
+        y_velocity:          word(0x06),
+        timer:               source[0x08],
+        fx_type:             FxType::from_byte(source[0x09]),
+        default_layer_blend: source[0x0A],
+        layer_3_blend:       source[0x0B],
+        liquid_options:      source[0x0C],
+        palette_fx:          source[0x0D],
+        animated_tiles:      source[0x0E],
+        palette_blend:       source[0x0F],
+    }
+}
+
+impl Fx {
+    pub fn to_bytes(&self) -> [u8; FX_BYTE_SIZE] {
+        let [door_low, door_high] = self.door.to_le_bytes();
+        let [base_low, base_high] = self.base_y.to_le_bytes();
+        let [target_low, target_high] = self.target_y.to_le_bytes();
+        let [velocity_low, velocity_high] = self.y_velocity.to_le_bytes();
+        [
+            door_low,
+            door_high,
+            base_low,
+            base_high,
+            target_low,
+            target_high,
+            velocity_low,
+            velocity_high,
+            self.timer,
+            self.fx_type.to_byte(),
+            self.default_layer_blend,
+            self.layer_3_blend,
+            self.liquid_options,
+            self.palette_fx,
+            self.animated_tiles,
+            self.palette_blend,
+        ]
+    }
+}
+
+/// Load entries until the one for any Door, the $FFFF terminator, or the end of 'source'.
+pub fn load_bytes(source: &[u8]) -> FxList {
+    let mut fx_list = Vec::new();
+    for bytes in source.chunks_exact(FX_BYTE_SIZE) {
+        let fx = fx_from_bytes(bytes);
+        if fx.door == TERMINATOR {
+            break;
+        }
+        fx_list.push(fx);
+        if fx.door == 0 {
+            break;
+        }
+    }
+    fx_list
+}
+
+/// Convert the entries into bytes, ending with $FFFF unless the last one is for any Door.
+pub fn to_bytes(fx_list: &[Fx]) -> Vec<u8> {
+    let mut output: Vec<u8> = fx_list.iter().flat_map(Fx::to_bytes).collect();
+    if fx_list.last().is_none_or(|fx| fx.door != 0) {
+        output.extend(TERMINATOR.to_le_bytes());
+    }
+    output
+}
+
+/// The entry used when Samus comes from any Door, which is the last one.
+pub fn default_fx(fx_list: &[Fx]) -> Option<&Fx> {
+    fx_list.last().filter(|fx| fx.door == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Load FX entries from bytes and convert them back.
+    #[test]
+    fn load_fx_from_bytes() {
+        #[rustfmt::skip]
+        let data = [
+            0x16, 0x89,             // door
+            0xD0, 0x00,             // base_y
+            0x30, 0x00,             // target_y
+            0xC0, 0xFF,             // y_velocity
+            0x20,                   // timer
+            0x02,                   // fx_type
+            0x02, 0x1E, 0x00,       // layer blends and liquid_options
+            0x01, 0x02, 0x00,       // palette_fx, animated_tiles, palette_blend
+            0x00, 0x00,             // door
+            0xA8, 0x01,             // base_y
+            0xFF, 0xFF,             // target_y
+            0x00, 0x00,             // y_velocity
+            0x00,                   // timer
+            0x06,                   // fx_type
+            0x02, 0x18, 0x00,       // layer blends and liquid_options
+            0x00, 0x00, 0x00,       // palette_fx, animated_tiles, palette_blend
+            0xAA, 0xAA,
+        ];
+
+        let fx_list = load_bytes(&data);
+        assert_eq!(fx_list.len(), 2);
+        assert_eq!(fx_list[0].door, 0x8916);
+        assert_eq!(fx_list[0].fx_type, FxType::Lava);
+        assert_eq!(fx_list[0].y_velocity, 0xFFC0);
+        assert_eq!(fx_list[0].palette_fx, 0x01);
+        assert_eq!(default_fx(&fx_list).unwrap().fx_type, FxType::Water);
+        assert_eq!(default_fx(&fx_list).unwrap().base_y, 0x01A8);
+        assert_eq!(to_bytes(&fx_list), data[..data.len() - 2]);
+
+        // Without an entry for any Door, the list ends with $FFFF.
+        let fx_list = load_bytes(&[&data[..FX_BYTE_SIZE], &[0xFF, 0xFF]].concat());
+        assert_eq!(fx_list.len(), 1);
+        assert!(default_fx(&fx_list).is_none());
+        assert_eq!(to_bytes(&fx_list)[FX_BYTE_SIZE..], [0xFF, 0xFF]);
+        assert!(load_bytes(&[0xFF, 0xFF]).is_empty());
+        assert_eq!(to_bytes(&[]), [0xFF, 0xFF]);
+    }
+
+    /// Convert FX types from and into their value in ROM, keeping unknown ones.
+    #[test]
+    fn convert_fx_types() {
+        assert_eq!(FxType::from_byte(0x04), FxType::Acid);
+        assert_eq!(FxType::from_byte(0x2C), FxType::Haze);
+        assert_eq!(FxType::from_byte(0x30), FxType::Other(0x30));
+        for value in 0..=0xFF {
+            assert_eq!(FxType::from_byte(value).to_byte(), value);
+        }
+        assert!(FxType::Water.is_liquid());
+        assert!(!FxType::Fog.is_liquid());
+    }
+}
@@ -12,6 +12,7 @@ use crate::{
 use super::tile_table::{TileTable, BLOCK_SIZE, TILES_BY_BLOCK};
 
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Block {
     pub block_type: BlockType, // Specifies the primary type of the block.
     pub y_flip: bool,          // Flips the graphics of the block at Y axis.
@@ -20,6 +21,7 @@ pub struct Block {
 }
 
 #[derive(Debug, Default, Clone, Copy, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BlockType {
     #[default]
     Air,
@@ -45,6 +47,7 @@ pub type BtsBlock = u8;
 pub const BLOCKS_PER_SCREEN: usize = 16;
 
 #[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LevelData {
     pub layer1: Vec<Block>,
     pub bts: Vec<BtsBlock>,
@@ -96,7 +99,7 @@ impl From<i32> for BlockType {
 /// Level Data format reference: https://wiki.metroidconstruction.com/doku.php?id=super:technical_information:data_structures#level_data
 pub fn load_from_bytes(source: &[u8], has_layer2: bool) -> Result<LevelData, ParseError> {
     if source.len() < 2 {
-        return Err(ParseError);
+        return Err(ParseError::Truncated);
     };
 
     let layer_size = u16::from_le_bytes([source[0], source[1]]) as usize;
@@ -106,7 +109,7 @@ pub fn load_from_bytes(source: &[u8], has_layer2: bool) -> Result<LevelData, Par
     if source.len()
         < number_of_blocks * 2 + number_of_blocks + number_of_blocks * 2 * (has_layer2 as usize)
     {
-        return Err(ParseError);
+        return Err(ParseError::Truncated);
     };
 
     let layer1: Vec<Block> = layer_from_bytes(&source[..number_of_blocks * 2]); // Each block is 2 bytes.
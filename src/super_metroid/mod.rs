@@ -1,40 +1,59 @@
 pub mod address;
 pub mod door;
 pub mod door_list;
+pub mod enemy;
+pub mod enemy_species;
+pub mod fx;
 pub mod level_data;
+pub mod plm;
+pub mod progress;
 pub mod room;
 pub mod save_station;
+pub mod spritemap;
 pub mod state;
 pub mod tile_table;
 pub mod tileset;
 
 use std::{
     collections::{hash_map::Entry, HashMap},
-    error::Error,
-    fs,
+    fs, io,
+    ops::Range,
 };
 
 use crate::{
-    address::{LoRom, Pc},
-    compress::{lz5_compress, lz5_decompress},
+    address::{LoRom, Mapping, Pc},
+    compress::{lz5_compress, lz5_decompress_with_size},
+    free_space::{FreeSpace, FreeSpaceError, BANK_SIZE},
     graphics::{
         gfx::{self, Gfx, TileGfx},
-        palette, Palette,
+        palette::{self, COLORS_BY_SUB_PALETTE},
+        Palette,
     },
-    ParseError,
+    header::{self, Header},
+    patch::{bps, ips},
+    Error, ParseError, Structure,
 };
 
-use address::{CRE_GFX, CRE_TILESET, DOORS_LIST, ROOMS, TILESETS};
+use address::{
+    CRE_GFX, CRE_TILESET, DOORS_BANK, ENEMY_NAMES_BANK, ENEMY_POPULATIONS_BANK, ENEMY_SETS_BANK,
+    ENEMY_SPECIES_BANK, ENTRANCES, FREE_SPACE, FX_BANK, NUMBER_OF_AREAS, RESTRICTED_BANKS,
+    ROOMS_BANK, SAVE_STATIONS_BANK, SAVE_STATIONS_LIST, TILESETS,
+};
 use door_list::DoorList;
+use enemy::{EnemyPopulation, EnemySet};
+use enemy_species::{EnemySpecies, ENEMY_SPECIES_BYTE_SIZE};
+use fx::FxList;
 use level_data::LevelData;
+use plm::PlmPopulation;
+use progress::Boss;
 use room::Room;
-use state::State;
+use spritemap::Spritemap;
+use state::{State, STATE_BYTE_SIZE};
 use tile_table::TileTable;
 
 use self::{
-    address::DOORS,
     door::{Door, DOOR_BYTE_SIZE},
-    save_station::SaveStation,
+    save_station::{SaveStation, SAVE_STATION_BYTE_SIZE},
     tileset::Tileset,
 };
 
@@ -43,9 +62,41 @@ pub const UNHEADERED_MD5: [u8; 16] = [
     33, 243, 233, 141, 244, 120, 14, 225, 198, 103, 184, 78, 87, 216, 134, 117,
 ];
 
+pub const TITLE: &str = "Super Metroid";
+
+/// Largest LoRom, and largest ExLoRom as banks $7E-$7F are RAM.
+pub const MAX_LO_ROM_SIZE: usize = 0x40_0000;
+pub const MAX_EX_LO_ROM_SIZE: usize = 0x7F_0000;
+
+/// Bytes of an initialization AI searched for the instruction list it sets.
+const INIT_AI_SEARCH_SIZE: usize = 0x40;
+
+/// Known base version of a loaded ROM.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum RomVersion {
+    #[default]
+    JapanUsa,
+    Pal,
+    ModifiedJapanUsa,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct LoadOptions {
+    /// Only accept the vanilla (JU) ROM, verified by its MD5.
+    pub vanilla_only: bool,
+    /// IPS or BPS patches applied in order before parsing.
+    pub patches: Vec<Vec<u8>>,
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct SuperMetroid {
     pub rom: Vec<u8>,
+    /// ROM as it was before any patch or change, to create patches from.
+    pub original_rom: Vec<u8>,
+    pub copier_header: Option<Vec<u8>>,
+    pub header: Header,
+    pub mapping: Mapping,
+    pub version: RomVersion,
     pub cre_gfx: Gfx,
     pub cre_tileset: TileTable,
     pub tilesets: Vec<Tileset>,
@@ -57,7 +108,17 @@ pub struct SuperMetroid {
     pub states: HashMap<usize, State>,
     pub doors: HashMap<usize, Door>,
     pub door_lists: HashMap<usize, DoorList>,
+    pub plm_populations: HashMap<usize, PlmPopulation>,
+    pub enemy_populations: HashMap<usize, EnemyPopulation>,
+    pub enemy_sets: HashMap<usize, EnemySet>,
+    pub enemy_species: HashMap<usize, EnemySpecies>,
+    pub fx: HashMap<usize, FxList>,
     pub save_stations: Vec<Vec<SaveStation>>,
+    pub free_space: FreeSpace,
+    /// Number of bytes each asset occupies in ROM, by its LoRom address.
+    pub asset_sizes: HashMap<usize, usize>,
+    /// Non-fatal failures found while loading. The ROM is still usable without these assets.
+    pub diagnostics: Vec<Error>,
 }
 
 impl SuperMetroid {
@@ -107,9 +168,21 @@ impl SuperMetroid {
         (palette, graphics, tile_table)
     }
 
-    pub fn save_to_rom(&mut self) {
-        self.save_palettes_to_rom();
-        self.save_level_data_to_rom();
+    pub fn save_to_rom(&mut self) -> Result<(), Error> {
+        self.save_palettes_to_rom()?;
+        self.save_graphics_to_rom()?;
+        self.save_tile_tables_to_rom()?;
+        self.save_cre_to_rom()?;
+        self.save_level_data_to_rom()?;
+        self.save_door_lists_to_rom()?;
+        self.save_plm_populations_to_rom()?;
+        self.save_enemy_populations_to_rom()?;
+        self.save_enemy_sets_to_rom()?;
+        self.save_fx_to_rom()?;
+        self.save_rooms_to_rom()?;
+        self.save_doors_to_rom();
+        self.save_enemy_species_to_rom();
+        self.save_save_stations_to_rom()?;
 
         // Write tilesets to ROM.
         let tileset_address: Pc = LoRom { address: TILESETS }.into();
@@ -121,308 +194,2362 @@ impl SuperMetroid {
             tileset_address.address..tileset_address.address + tilesets_as_bytes.len(),
             tilesets_as_bytes,
         );
+
+        Ok(())
     }
 
-    pub fn save_to_file(&mut self, filename: &str) -> std::io::Result<()> {
-        fs::write(filename, &self.rom)
+    /// Save the ROM, with the copier header it was loaded with if 'keep_copier_header' is set.
+    /// A ROM loaded without a copier header is always saved without one.
+    pub fn save_to_file(
+        &mut self,
+        filename: &str,
+        keep_copier_header: bool,
+    ) -> std::io::Result<()> {
+        self.update_header().map_err(io::Error::other)?;
+        match &self.copier_header {
+            Some(copier_header) if keep_copier_header => {
+                fs::write(filename, [&copier_header[..], &self.rom[..]].concat())
+            }
+            _ => fs::write(filename, &self.rom),
+        }
     }
 
-    pub fn save_palettes_to_rom(&mut self) -> HashMap<usize, usize> {
-        let mut remapped_addresses: HashMap<usize, usize> = HashMap::new();
-        let mut pc_to_write: Pc = LoRom { address: 0xC2AD7C }.into();
+    /// Save an IPS patch with all changes made to the original ROM.
+    pub fn save_ips_patch(&mut self, filename: &str) -> std::io::Result<()> {
+        self.update_header().map_err(io::Error::other)?;
+        fs::write(filename, ips::create(&self.original_rom, &self.rom))
+    }
 
-        // Compress every palette and save to ROM.
-        for (pal_address, palette) in self.palettes.iter() {
-            let pal_compressed_bytes = lz5_compress(&palette.to_bytes());
-            let number_of_bytes = pal_compressed_bytes.len();
+    /// Save a BPS patch with all changes made to the original ROM.
+    pub fn save_bps_patch(&mut self, filename: &str) -> std::io::Result<()> {
+        self.update_header().map_err(io::Error::other)?;
+        fs::write(filename, bps::create(&self.original_rom, &self.rom))
+    }
 
-            self.rom.splice(
-                pc_to_write.address..pc_to_write.address + number_of_bytes,
-                pal_compressed_bytes,
+    /// Expand the ROM to 'size' bytes filled with $FF, registering the new banks as free space.
+    /// Past 4 MB the ROM becomes an ExLoRom, which boots from bank $00, so the first bank is copied there.
+    pub fn expand(&mut self, size: usize) -> Result<(), Error> {
+        let old_size = self.rom.len();
+        if size < old_size || !size.is_multiple_of(BANK_SIZE) || size > MAX_EX_LO_ROM_SIZE {
+            return Err(Error::InvalidRomSize { size });
+        }
+
+        self.rom.resize(size, 0xFF);
+        self.header.rom_size = header::rom_size_byte(size);
+
+        if size > MAX_LO_ROM_SIZE && old_size <= MAX_LO_ROM_SIZE {
+            self.mapping = Mapping::ExLoRom;
+            self.free_space
+                .free(Pc { address: old_size }, MAX_LO_ROM_SIZE - old_size);
+            self.free_space.free(
+                Pc {
+                    address: MAX_LO_ROM_SIZE + BANK_SIZE,
+                },
+                size - MAX_LO_ROM_SIZE - BANK_SIZE,
             );
+            self.rom.copy_within(..BANK_SIZE, MAX_LO_ROM_SIZE);
+        } else {
+            self.free_space
+                .free(Pc { address: old_size }, size - old_size);
+        }
+
+        Ok(())
+    }
+
+    /// Whether the checksum and its complement in the header match the ROM.
+    pub fn has_valid_checksum(&self) -> bool {
+        self.header.has_valid_complement() && self.header.checksum == header::checksum(&self.rom)
+    }
+
+    /// Write the header with its checksum recomputed where its mapping places it,
+    /// also in bank $00 when it's an ExLoRom. Fails if the ROM is too small to hold it there.
+    fn update_header(&mut self) -> Result<(), Error> {
+        let positions = match self.mapping {
+            Mapping::LoRom => vec![header::LO_ROM_HEADER],
+            Mapping::ExLoRom => vec![header::LO_ROM_HEADER, header::EX_LO_ROM_HEADER],
+            Mapping::HiRom => vec![header::HI_ROM_HEADER],
+            Mapping::ExHiRom => vec![header::EX_HI_ROM_HEADER],
+        };
+        if positions
+            .iter()
+            .any(|position| position + header::HEADER_SIZE > self.rom.len())
+        {
+            return Err(Error::InvalidRomSize {
+                size: self.rom.len(),
+            });
+        }
+
+        self.header.checksum_complement = 0xFFFF;
+        self.header.checksum = 0x0000;
+        for position in positions.iter() {
+            self.rom[*position..*position + header::HEADER_SIZE]
+                .copy_from_slice(&self.header.to_bytes());
+        }
 
-            remapped_addresses.insert(*pal_address, LoRom::from(pc_to_write).address);
-            pc_to_write.address += number_of_bytes;
+        self.header.checksum = header::checksum(&self.rom);
+        self.header.checksum_complement = !self.header.checksum;
+        for position in positions {
+            self.rom[position..position + header::HEADER_SIZE]
+                .copy_from_slice(&self.header.to_bytes());
         }
 
+        Ok(())
+    }
+
+    /// Save every changed palette anywhere in free space, reusing the space of all of them.
+    /// Tilesets references to relocated palettes are changed accordingly.
+    pub fn save_palettes_to_rom(&mut self) -> Result<HashMap<usize, usize>, Error> {
+        let mut addresses: Vec<usize> = self.palettes.keys().copied().collect();
+        addresses.sort_unstable();
+        let changed_palettes: Vec<(usize, Vec<u8>)> = addresses
+            .into_iter()
+            .map(|address| (address, self.palettes[&address].to_bytes()))
+            .filter(|(address, data)| !self.is_unchanged(Structure::Palette, *address, data))
+            .map(|(address, data)| (address, lz5_compress(&data)))
+            .collect();
+        let remapped_addresses = self.rewrite_all_assets(Structure::Palette, changed_palettes)?;
+
         // Update palette list addresses.
         self.palettes =
             self.palettes
                 .iter()
                 .fold(HashMap::new(), |mut accum, (address, palette)| {
-                    accum.insert(remapped_addresses[address], *palette);
+                    accum.insert(
+                        *remapped_addresses.get(address).unwrap_or(address),
+                        *palette,
+                    );
                     accum
                 });
 
         // Tileset addresses references needs to be changed accordingly.
         for tileset in self.tilesets.iter_mut() {
-            tileset.palette = remapped_addresses[&(tileset.palette as usize)] as u32;
+            if let Some(address) = remapped_addresses.get(&(tileset.palette as usize)) {
+                tileset.palette = *address as u32;
+            }
         }
 
-        remapped_addresses
+        Ok(remapped_addresses)
     }
 
-    pub fn save_level_data_to_rom(&mut self) -> HashMap<usize, usize> {
+    /// Save every changed Gfx in-place, or anywhere in free space if it grew bigger.
+    /// Tilesets references to relocated Gfx are changed accordingly.
+    pub fn save_graphics_to_rom(&mut self) -> Result<HashMap<usize, usize>, Error> {
         let mut remapped_addresses: HashMap<usize, usize> = HashMap::new();
-        let mut pc_to_write: Pc = LoRom { address: 0xC2C2BB }.into();
 
-        // Compress every level data and save to ROM.
-        for (level_address, level) in self.levels.iter() {
-            let level_compressed_bytes = lz5_compress(&level.to_bytes());
-            let number_of_bytes = level_compressed_bytes.len();
+        let mut addresses: Vec<usize> = self.graphics.keys().copied().collect();
+        addresses.sort_unstable();
+        for gfx_address in addresses {
+            let gfx_bytes = self.graphics[&gfx_address].to_4bpp();
+            if self.is_unchanged(Structure::Gfx, gfx_address, &gfx_bytes) {
+                continue;
+            }
+            let gfx_compressed_bytes = lz5_compress(&gfx_bytes);
+            let new_address =
+                self.rewrite_asset(Structure::Gfx, gfx_address, gfx_compressed_bytes)?;
+            if new_address != gfx_address {
+                remapped_addresses.insert(gfx_address, new_address);
+            }
+        }
+
+        for (old_address, new_address) in remapped_addresses.iter() {
+            if let Some(gfx) = self.graphics.remove(old_address) {
+                self.graphics.insert(*new_address, gfx);
+            }
+        }
 
-            self.rom.splice(
-                pc_to_write.address..pc_to_write.address + number_of_bytes,
-                level_compressed_bytes,
-            );
+        for tileset in self.tilesets.iter_mut() {
+            if let Some(address) = remapped_addresses.get(&(tileset.graphic as usize)) {
+                tileset.graphic = *address as u32;
+            }
+        }
+
+        Ok(remapped_addresses)
+    }
+
+    /// Save every changed Tile Table in-place, or anywhere in free space if it grew bigger.
+    /// Tilesets references to relocated Tile Tables are changed accordingly.
+    pub fn save_tile_tables_to_rom(&mut self) -> Result<HashMap<usize, usize>, Error> {
+        let mut remapped_addresses: HashMap<usize, usize> = HashMap::new();
+
+        let mut addresses: Vec<usize> = self.tile_tables.keys().copied().collect();
+        addresses.sort_unstable();
+        for tile_table_address in addresses {
+            let tile_table_bytes = tile_table::to_bytes(&self.tile_tables[&tile_table_address]);
+            if self.is_unchanged(Structure::TileTable, tile_table_address, &tile_table_bytes) {
+                continue;
+            }
+            let tile_table_compressed_bytes = lz5_compress(&tile_table_bytes);
+            let new_address = self.rewrite_asset(
+                Structure::TileTable,
+                tile_table_address,
+                tile_table_compressed_bytes,
+            )?;
+            if new_address != tile_table_address {
+                remapped_addresses.insert(tile_table_address, new_address);
+            }
+        }
+
+        for (old_address, new_address) in remapped_addresses.iter() {
+            if let Some(tile_table) = self.tile_tables.remove(old_address) {
+                self.tile_tables.insert(*new_address, tile_table);
+            }
+        }
+
+        for tileset in self.tilesets.iter_mut() {
+            if let Some(address) = remapped_addresses.get(&(tileset.tile_table as usize)) {
+                tileset.tile_table = *address as u32;
+            }
+        }
 
-            remapped_addresses.insert(*level_address, LoRom::from(pc_to_write).address);
-            pc_to_write.address += number_of_bytes;
+        Ok(remapped_addresses)
+    }
+
+    /// Save the CRE Gfx and Tileset in-place if they changed, as the game code points to them.
+    pub fn save_cre_to_rom(&mut self) -> Result<(), Error> {
+        for (structure, address, data) in [
+            (Structure::Gfx, CRE_GFX, self.cre_gfx.to_4bpp()),
+            (
+                Structure::TileTable,
+                CRE_TILESET,
+                tile_table::to_bytes(&self.cre_tileset),
+            ),
+        ] {
+            if self.is_unchanged(structure, address, &data) {
+                continue;
+            }
+            let compressed_bytes = lz5_compress(&data);
+            match self.asset_sizes.get(&address) {
+                Some(size) if compressed_bytes.len() <= *size => {
+                    let pc_to_write = self.mapping.to_pc(address);
+                    self.rom.splice(
+                        pc_to_write.address..pc_to_write.address + compressed_bytes.len(),
+                        compressed_bytes,
+                    );
+                }
+                _ => {
+                    return Err(Error::OutOfFreeSpace {
+                        structure,
+                        size: compressed_bytes.len(),
+                    })
+                }
+            }
         }
 
+        Ok(())
+    }
+
+    /// Save every changed Level Data anywhere in free space, reusing the space of all of them.
+    /// States references to relocated Level Data are changed accordingly.
+    pub fn save_level_data_to_rom(&mut self) -> Result<HashMap<usize, usize>, Error> {
+        let mut addresses: Vec<usize> = self.levels.keys().copied().collect();
+        addresses.sort_unstable();
+        let changed_levels: Vec<(usize, Vec<u8>)> = addresses
+            .into_iter()
+            .map(|address| (address, self.levels[&address].to_bytes()))
+            .filter(|(address, data)| !self.is_unchanged(Structure::LevelData, *address, data))
+            .map(|(address, data)| (address, lz5_compress(&data)))
+            .collect();
+        let remapped_addresses = self.rewrite_all_assets(Structure::LevelData, changed_levels)?;
+
         // Update levels list addresses.
         self.levels = self
             .levels
             .iter()
             .fold(HashMap::new(), |mut accum, (address, level)| {
-                accum.insert(remapped_addresses[address], level.clone());
+                accum.insert(
+                    *remapped_addresses.get(address).unwrap_or(address),
+                    level.clone(),
+                );
                 accum
             });
 
         // State addresses references to Levels needs to be changed accordingly.
         for state in self.states.values_mut() {
-            state.level_address = remapped_addresses[&(state.level_address as usize)] as u32;
+            if let Some(address) = remapped_addresses.get(&(state.level_address as usize)) {
+                state.level_address = *address as u32;
+            }
         }
 
-        // Save all Rooms in-place. TODO: They should be saved in any place.
-        for (room_address, room) in &self.rooms {
+        Ok(remapped_addresses)
+    }
+
+    /// Save every Room and its States in-place, or followed by its States anywhere in free space
+    /// of the rooms bank if it grew bigger. Pointers to relocated Rooms and States are changed.
+    pub fn save_rooms_to_rom(&mut self) -> Result<HashMap<usize, usize>, Error> {
+        let mut remapped_addresses: HashMap<usize, usize> = HashMap::new();
+        let mut rooms = HashMap::new();
+        let mut states = HashMap::new();
+
+        // The game compares some Room pointers, so only the ones which don't fit are moved.
+        let mut addresses: Vec<usize> = self.rooms.keys().copied().collect();
+        addresses.sort_unstable();
+        let (addresses_in_place, addresses_to_move): (Vec<usize>, Vec<usize>) = addresses
+            .into_iter()
+            .partition(|address| self.room_fits_in_place(*address));
+
+        for room_address in addresses_in_place {
+            let room = self.rooms[&room_address].clone();
+            let pc_to_write = self.mapping.to_pc(room_address);
             let room_data = room.to_bytes();
-            let pc_to_write_room: Pc = LoRom {
-                address: *room_address,
+            self.rom.splice(
+                pc_to_write.address..pc_to_write.address + room_data.len(),
+                room_data,
+            );
+
+            for state_condition in room.state_conditions.iter() {
+                let state = self.states[&(state_condition.state_address as usize)];
+                let pc_to_write = self
+                    .mapping
+                    .to_pc(ROOMS_BANK + state_condition.state_address as usize);
+                self.rom.splice(
+                    pc_to_write.address..pc_to_write.address + STATE_BYTE_SIZE,
+                    state.to_bytes(),
+                );
+                states.insert(state_condition.state_address as usize, state);
             }
-            .into();
+            rooms.insert(room_address, room);
+        }
 
-            let room_end_address = pc_to_write_room.address + room_data.len();
-            self.rom
-                .splice(pc_to_write_room.address..room_end_address, room_data);
+        // The moved Rooms and States are written again, so all their space can be reused.
+        for address in addresses_to_move.iter() {
+            self.release_asset(*address);
+            for state_condition in self.rooms[address].state_conditions.clone() {
+                self.release_asset(ROOMS_BANK + state_condition.state_address as usize);
+            }
+        }
 
-            let states_data =
-                room.state_conditions
-                    .iter()
-                    .fold(Vec::new(), |mut acc, state_condition| {
-                        acc.extend(
-                            self.states[&(state_condition.state_address as usize)].to_bytes(),
-                        );
-                        acc
-                    });
+        for room_address in addresses_to_move {
+            let mut room = self.rooms[&room_address].clone();
+
+            // The default State must come right after the Room, followed by all others.
+            let room_size = room.to_bytes().len();
+            let pc_to_write = self.allocate_in_bank(
+                Structure::Room,
+                room_size + room.state_conditions.len() * STATE_BYTE_SIZE,
+                ROOMS_BANK,
+            )?;
+            let new_room_address = LoRom::from(pc_to_write).address;
+
+            let mut states_data = Vec::new();
+            for (i, state_condition) in room.state_conditions.iter_mut().enumerate() {
+                let state = self.states[&(state_condition.state_address as usize)];
+                let state_address = new_room_address + room_size + i * STATE_BYTE_SIZE;
+
+                state_condition.state_address = state_address as u16;
+                states.insert(state_address & 0xFFFF, state);
+                states_data.extend(state.to_bytes());
+                self.asset_sizes.insert(state_address, STATE_BYTE_SIZE);
+            }
+
+            let room_data = [room.to_bytes(), states_data].concat();
             self.rom.splice(
-                room_end_address..room_end_address + states_data.len(),
-                states_data,
+                pc_to_write.address..pc_to_write.address + room_data.len(),
+                room_data,
             );
+            self.asset_sizes.insert(new_room_address, room_size);
+
+            remapped_addresses.insert(room_address, new_room_address);
+            rooms.insert(new_room_address, room);
+        }
+
+        self.rooms = rooms;
+        self.states = states;
+
+        // Doors and Save Stations references to Rooms needs to be changed accordingly.
+        for door in self.doors.values_mut() {
+            if let Some(address) =
+                remapped_addresses.get(&(ROOMS_BANK + door.destination_room as usize))
+            {
+                door.destination_room = *address as u16;
+            }
+        }
+        for save_station in self.save_stations.iter_mut().flatten() {
+            if let Some(address) =
+                remapped_addresses.get(&(ROOMS_BANK + save_station.room_pointer as usize))
+            {
+                save_station.room_pointer = *address as u16;
+            }
         }
 
-        remapped_addresses
+        Ok(remapped_addresses)
     }
 
-    fn check_md5(&self) -> bool {
-        md5::compute(&self.rom).0 == UNHEADERED_MD5
+    /// Whether the Room at 'room_address' can be written over itself, still followed by its
+    /// default State, with every other State written over itself too.
+    fn room_fits_in_place(&self, room_address: usize) -> bool {
+        let room = &self.rooms[&room_address];
+        let room_size = room.to_bytes().len();
+        let has_state_size = |state_address: u16| {
+            self.asset_sizes
+                .get(&(ROOMS_BANK + state_address as usize))
+                .is_some_and(|size| *size >= STATE_BYTE_SIZE)
+        };
+
+        self.asset_sizes.get(&room_address) == Some(&room_size)
+            && room.state_conditions.first().is_some_and(|default| {
+                ROOMS_BANK + default.state_address as usize == room_address + room_size
+            })
+            && room
+                .state_conditions
+                .iter()
+                .all(|state_condition| has_state_size(state_condition.state_address))
     }
-}
 
-pub fn load_unheadered_rom(data: Vec<u8>) -> Result<SuperMetroid, Box<dyn Error>> {
-    let mut sm = SuperMetroid {
-        rom: data,
-        ..Default::default()
-    };
+    /// Save every Door List in-place, or anywhere in the rooms bank if it grew bigger.
+    /// Rooms references to relocated Door Lists are changed accordingly.
+    pub fn save_door_lists_to_rom(&mut self) -> Result<HashMap<usize, usize>, Error> {
+        let mut remapped_addresses: HashMap<usize, usize> = HashMap::new();
+
+        let mut addresses: Vec<usize> = self.door_lists.keys().copied().collect();
+        addresses.sort_unstable();
+        for door_list_address in addresses {
+            let door_list_data = door_list::to_bytes(&self.door_lists[&door_list_address]);
+            let number_of_bytes = door_list_data.len();
 
-    if !sm.check_md5() {
-        return Err(Box::new(ParseError));
+            let pc_to_write = match self.asset_sizes.get(&door_list_address) {
+                Some(size) if number_of_bytes <= *size => LoRom {
+                    address: door_list_address,
+                }
+                .into(),
+                _ => {
+                    self.release_asset(door_list_address);
+                    let pc_to_write =
+                        self.allocate_in_bank(Structure::DoorList, number_of_bytes, ROOMS_BANK)?;
+                    self.asset_sizes
+                        .insert(LoRom::from(pc_to_write).address, number_of_bytes);
+                    remapped_addresses.insert(door_list_address, LoRom::from(pc_to_write).address);
+                    pc_to_write
+                }
+            };
+
+            self.rom.splice(
+                pc_to_write.address..pc_to_write.address + number_of_bytes,
+                door_list_data,
+            );
+        }
+
+        // Update door lists addresses.
+        for (old_address, new_address) in remapped_addresses.iter() {
+            if let Some(door_list) = self.door_lists.remove(old_address) {
+                self.door_lists.insert(*new_address, door_list);
+            }
+        }
+
+        // Room addresses references to Door Lists needs to be changed accordingly.
+        for room in self.rooms.values_mut() {
+            if let Some(address) = remapped_addresses.get(&(ROOMS_BANK + room.doors as usize)) {
+                room.doors = *address as u16;
+            }
+        }
+
+        Ok(remapped_addresses)
     }
 
-    // Load all Tilesets.
-    sm.tilesets = tileset::from_bytes(
-        &sm.rom.offset(LoRom { address: TILESETS }.into())
-            [..tileset::TILESET_DATA_SIZE * tileset::NUMBER_OF_TILESETS],
-    );
-    // Load all Tilesets.
-    for tileset in sm.tilesets.iter() {
-        // Load it's Palette.
-        sm.palettes
-            .entry(tileset.palette as usize)
-            .or_insert(palette::from_bytes(&lz5_decompress(
-                sm.rom.offset(
-                    LoRom {
-                        address: tileset.palette as usize,
-                    }
-                    .into(),
-                ),
-            )?)?);
+    /// Save every PLM population in-place, or anywhere in the rooms bank if it grew bigger.
+    /// States references to relocated PLM populations are changed accordingly.
+    pub fn save_plm_populations_to_rom(&mut self) -> Result<HashMap<usize, usize>, Error> {
+        self.save_state_assets_to_rom(
+            Structure::PlmPopulation,
+            ROOMS_BANK,
+            |sm| &mut sm.plm_populations,
+            |plm_population| plm::to_bytes(plm_population),
+            |state| &mut state.plm_population,
+        )
+    }
 
-        // Load it's Graphics.
-        sm.graphics
-            .entry(tileset.graphic as usize)
-            .or_insert(gfx::from_4bpp(&lz5_decompress(
-                sm.rom.offset(
-                    LoRom {
-                        address: tileset.graphic as usize,
-                    }
-                    .into(),
-                ),
-            )?));
+    /// Save every Enemy Population in-place, or anywhere in its bank if it grew bigger.
+    /// States references to relocated Enemy Populations are changed accordingly.
+    pub fn save_enemy_populations_to_rom(&mut self) -> Result<HashMap<usize, usize>, Error> {
+        self.save_state_assets_to_rom(
+            Structure::EnemyPopulation,
+            ENEMY_POPULATIONS_BANK,
+            |sm| &mut sm.enemy_populations,
+            EnemyPopulation::to_bytes,
+            |state| &mut state.enemy_population,
+        )
+    }
 
-        // Load all Tile Tables.
-        sm.tile_tables
-            .entry(tileset.tile_table as usize)
-            .or_insert(tile_table::from_bytes(&lz5_decompress(
-                sm.rom.offset(
-                    LoRom {
-                        address: tileset.tile_table as usize,
-                    }
-                    .into(),
-                ),
-            )?));
+    /// Save every Enemy Set in-place, or anywhere in its bank if it grew bigger.
+    /// States references to relocated Enemy Sets are changed accordingly.
+    pub fn save_enemy_sets_to_rom(&mut self) -> Result<HashMap<usize, usize>, Error> {
+        self.save_state_assets_to_rom(
+            Structure::EnemySet,
+            ENEMY_SETS_BANK,
+            |sm| &mut sm.enemy_sets,
+            |enemy_set| enemy::set_to_bytes(enemy_set),
+            |state| &mut state.enemy_set,
+        )
     }
 
-    // Load CRE graphic.
-    sm.cre_gfx = gfx::from_4bpp(&lz5_decompress(
-        sm.rom.offset(LoRom { address: CRE_GFX }.into()),
-    )?);
+    /// Save every FX list in-place, or anywhere in its bank if it grew bigger.
+    /// States references to relocated FX lists are changed accordingly.
+    pub fn save_fx_to_rom(&mut self) -> Result<HashMap<usize, usize>, Error> {
+        self.save_state_assets_to_rom(
+            Structure::Fx,
+            FX_BANK,
+            |sm| &mut sm.fx,
+            |fx_list| fx::to_bytes(fx_list),
+            |state| &mut state.fx,
+        )
+    }
 
-    // Load CRE tileset.
-    sm.cre_tileset = tile_table::from_bytes(&lz5_decompress(
-        sm.rom.offset(
-            LoRom {
-                address: CRE_TILESET,
+    /// Save every asset of 'structure' in 'assets' in-place, or anywhere in 'bank' if it grew bigger.
+    /// The 'pointer' of every State to a relocated asset is changed accordingly.
+    fn save_state_assets_to_rom<T>(
+        &mut self,
+        structure: Structure,
+        bank: usize,
+        assets: fn(&mut SuperMetroid) -> &mut HashMap<usize, T>,
+        to_bytes: fn(&T) -> Vec<u8>,
+        pointer: fn(&mut State) -> &mut u16,
+    ) -> Result<HashMap<usize, usize>, Error> {
+        let mut remapped_addresses: HashMap<usize, usize> = HashMap::new();
+
+        let mut addresses: Vec<usize> = assets(self).keys().copied().collect();
+        addresses.sort_unstable();
+        for address in addresses {
+            let data = to_bytes(&assets(self)[&address]);
+            let new_address = self.rewrite_asset_in_bank(structure, address, data, bank)?;
+            if new_address != address {
+                remapped_addresses.insert(address, new_address);
             }
-            .into(),
-        ),
-    )?);
+        }
 
-    // Load all Rooms.
-    for address in ROOMS {
-        sm.rooms.entry(*address).or_insert(room::from_bytes(
-            *address as u16,
-            sm.rom.offset(LoRom { address: *address }.into()),
-        ));
+        // All are removed before inserting any, as one may move where another was.
+        let moved_assets: Vec<(usize, T)> = remapped_addresses
+            .iter()
+            .filter_map(|(old_address, new_address)| {
+                assets(self)
+                    .remove(old_address)
+                    .map(|asset| (*new_address, asset))
+            })
+            .collect();
+        assets(self).extend(moved_assets);
+
+        for state in self.states.values_mut() {
+            let pointer = pointer(state);
+            if let Some(address) = remapped_addresses.get(&(bank + *pointer as usize)) {
+                *pointer = *address as u16;
+            }
+        }
+
+        Ok(remapped_addresses)
     }
 
-    // Load all Door Lists.
-    for door_list in DOORS_LIST {
-        sm.door_lists
-            .entry(door_list.1)
-            .or_insert(door_list::load_bytes(
-                door_list.0,
-                sm.rom.offset(
-                    LoRom {
-                        address: 0x8F_0000 + door_list.1 as usize,
-                    }
-                    .into(),
-                ),
-            ));
-    }
-
-    // Load all Doors.
-    let mut address: usize = DOORS.1;
-    for door in door::load_bytes(DOORS.0, sm.rom.offset(LoRom { address }.into())) {
-        sm.doors.insert(address, door);
-        address += DOOR_BYTE_SIZE;
-    }
-
-    // Load all StateConditions, States and LevelData.
-    for room in sm.rooms.values() {
-        for state_condition in room.state_conditions.iter() {
-            sm.states
-                .entry(state_condition.state_address as usize)
-                .or_insert(state::load_bytes(
-                    &sm.rom.offset(
-                        LoRom {
-                            address: 0x8F_0000 + state_condition.state_address as usize,
-                        }
-                        .into(),
-                    ),
-                ));
-
-            if let Some(state) = sm.states.get(&(state_condition.state_address as usize)) {
-                if let Entry::Vacant(entry) = sm.levels.entry(state.level_address as usize) {
-                    if let Ok(decompressed_data) = &lz5_decompress(
-                        &sm.rom.offset(
-                            LoRom {
-                                address: state.level_address as usize,
-                            }
-                            .into(),
-                        ),
-                    ) {
-                        if let Ok(level) = level_data::load_from_bytes(
-                            &decompressed_data,
-                            state.layer_2_x_scroll & state.layer_2_y_scroll & 1 == 0,
-                        ) {
-                            entry.insert(level);
-                        } else {
-                            println!("Could not load Level Data at 0x{:x}", state.level_address);
-                        }
-                    } else {
-                        println!(
-                            "Could not decompress Level Data at 0x{:x}",
-                            state.level_address
-                        );
-                    }
+    /// Load the FX list of every State.
+    fn load_fx(&mut self) {
+        for address in self.state_pointers(FX_BANK, |state| state.fx) {
+            let fx_list = fx::load_bytes(self.rom.offset(LoRom { address }.into()));
+            self.asset_sizes
+                .insert(address, fx::to_bytes(&fx_list).len());
+            self.fx.insert(address, fx_list);
+        }
+    }
+
+    /// Addresses in 'bank' of what each State points to with 'pointer', without duplicates.
+    fn state_pointers(&self, bank: usize, pointer: impl Fn(&State) -> u16) -> Vec<usize> {
+        let mut addresses: Vec<usize> = self
+            .states
+            .values()
+            .map(|state| bank + pointer(state) as usize)
+            .collect();
+        addresses.sort_unstable();
+        addresses.dedup();
+        addresses
+    }
+
+    /// Load the State of every StateCondition of every Room.
+    /// States which fail to load are kept as diagnostics.
+    fn load_states(&mut self) {
+        let mut state_addresses: Vec<usize> = self
+            .rooms
+            .values()
+            .flat_map(|room| room.state_conditions.iter())
+            .map(|state_condition| state_condition.state_address as usize)
+            .collect();
+        state_addresses.sort_unstable();
+        state_addresses.dedup();
+
+        for state_address in state_addresses {
+            let address = ROOMS_BANK + state_address;
+            let pc: Pc = LoRom { address }.into();
+            match state::load_bytes(self.rom.get(pc.address..).unwrap_or_default()) {
+                Ok(state) => {
+                    self.asset_sizes.insert(address, STATE_BYTE_SIZE);
+                    self.states.insert(state_address, state);
                 }
+                Err(error) => self.diagnostics.push(Error::Parse {
+                    structure: Structure::State,
+                    address,
+                    error,
+                }),
             }
         }
     }
 
-    // Load all Save Stations.
-    sm.save_stations = save_station::load_all_from_list(
-        sm.rom.offset(
-            LoRom {
-                address: address::SAVE_STATIONS,
-            }
-            .into(),
-        ),
-        sm.rom.offset(
-            LoRom {
-                address: address::SAVE_STATIONS_LIST,
-            }
-            .into(),
-        ),
-        address::NUMBER_OF_AREAS,
-    );
+    /// Load the PLM population of every State.
+    fn load_plm_populations(&mut self) {
+        for address in self.state_pointers(ROOMS_BANK, |state| state.plm_population) {
+            let plm_population = plm::load_bytes(self.rom.offset(LoRom { address }.into()));
+            self.asset_sizes
+                .insert(address, plm::to_bytes(&plm_population).len());
+            self.plm_populations.insert(address, plm_population);
+        }
+    }
 
-    Ok(sm)
-}
+    /// Load the Enemy Population and Enemy Set of every State.
+    fn load_enemies(&mut self) {
+        for address in self.state_pointers(ENEMY_POPULATIONS_BANK, |state| state.enemy_population) {
+            let population = enemy::load_population(self.rom.offset(LoRom { address }.into()));
+            self.asset_sizes
+                .insert(address, population.to_bytes().len());
+            self.enemy_populations.insert(address, population);
+        }
 
-pub trait Offset {
-    fn offset(&self, start: Pc) -> &[u8];
-}
+        for address in self.state_pointers(ENEMY_SETS_BANK, |state| state.enemy_set) {
+            let enemy_set = enemy::load_set(self.rom.offset(LoRom { address }.into()));
+            self.asset_sizes
+                .insert(address, enemy::set_to_bytes(&enemy_set).len());
+            self.enemy_sets.insert(address, enemy_set);
+        }
+    }
 
-impl Offset for Vec<u8> {
-    fn offset(&self, start: Pc) -> &[u8] {
-        &self[start.address..]
+    /// Load the species of every enemy in the Enemy Populations and Enemy Sets.
+    fn load_enemy_species(&mut self) {
+        let mut addresses: Vec<usize> = self
+            .enemy_populations
+            .values()
+            .flat_map(|population| population.enemies.iter().map(|enemy| enemy.species))
+            .chain(
+                self.enemy_sets
+                    .values()
+                    .flatten()
+                    .map(|entry| entry.species),
+            )
+            .filter(|species| *species >= 0x8000)
+            .map(|species| ENEMY_SPECIES_BANK + species as usize)
+            .collect();
+        addresses.sort_unstable();
+        addresses.dedup();
+
+        for address in addresses {
+            let pc: Pc = LoRom { address }.into();
+            if let Some(source) = self
+                .rom
+                .get(pc.address..pc.address + ENEMY_SPECIES_BYTE_SIZE)
+            {
+                self.enemy_species
+                    .insert(address, enemy_species::load_bytes(source));
+            }
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::{tile_table::Tile, *};
+    /// The uncompressed tiles of the Enemy Species 'species', along with its palette.
+    pub fn species_graphics(&self, species: u16) -> Option<(Gfx, Palette)> {
+        let species = self
+            .enemy_species
+            .get(&(ENEMY_SPECIES_BANK + species as usize))?;
 
-    /// Load Super Metroid data from rom.
-    #[test]
-    #[ignore = "Requires ROM data to run, which is Copyrighted."]
-    fn load_super_metroid_data_from_rom() {
-        assert!(load_unheadered_rom(
-            fs::read("/home/rondao/dev/snes_data/test/Super Metroid (JU) [!].smc").unwrap()
+        let tiles_pc = self.mapping.to_pc(species.tile_data as usize);
+        let tiles = self
+            .rom
+            .get(tiles_pc.address..tiles_pc.address + species.tile_data_size as usize / 32 * 32)?;
+
+        // The palette is in the AI bank of the species.
+        let palette_pc = self
+            .mapping
+            .to_pc(((species.ai_bank as usize) << 16) + species.palette as usize);
+        let palette = palette::from_bytes(
+            self.rom
+                .get(palette_pc.address..palette_pc.address + COLORS_BY_SUB_PALETTE * 2)?,
         )
-        .is_ok());
+        .ok()?;
+
+        Some((gfx::from_4bpp(tiles), palette))
     }
 
-    /// Fail to load Super Metroid data from incorrect rom data.
-    #[test]
-    #[ignore = "TODO: Create generic file to represent incorrect ROM data."]
-    fn load_super_metroid_data_from_incorrect_rom() {
-        assert!(load_unheadered_rom(
-            fs::read("/home/rondao/dev/snes_data/test/Incorrect Super Metroid.smc").unwrap()
-        )
-        .is_err());
+    /// The spritemap an enemy of the Enemy Species 'species' shows first, found by following
+    /// the instruction list set by its initialization AI, all of them in its AI bank.
+    pub fn species_spritemap(&self, species: u16) -> Option<Spritemap> {
+        let species = self
+            .enemy_species
+            .get(&(ENEMY_SPECIES_BANK + species as usize))?;
+        let ai_bank = |pointer: u16| {
+            let pc = self
+                .mapping
+                .to_pc(((species.ai_bank as usize) << 16) + pointer as usize);
+            self.rom.get(pc.address..)
+        };
+
+        let init_ai = ai_bank(species.init_ai)?;
+        let instruction_list = spritemap::initial_instruction_list(
+            &init_ai[..init_ai.len().min(INIT_AI_SEARCH_SIZE)],
+        )?;
+        let spritemap = spritemap::first_spritemap(ai_bank(instruction_list)?)?;
+        Some(spritemap::load_bytes(ai_bank(spritemap)?))
+    }
+
+    /// Name of the Enemy Species 'species', read where its header points to in the ROM,
+    /// or else known for the vanilla bosses.
+    pub fn species_name(&self, species: u16) -> Option<String> {
+        self.enemy_species
+            .get(&(ENEMY_SPECIES_BANK + species as usize))
+            .and_then(|header| {
+                let pc = self.mapping.to_pc(ENEMY_NAMES_BANK + header.name as usize);
+                enemy_species::name_from_bytes(self.rom.get(pc.address..)?)
+            })
+            .or_else(|| enemy_species::species_name(species).map(str::to_string))
+    }
+
+    /// The species of 'boss', to change its health or damage.
+    pub fn boss_species_mut(&mut self, boss: Boss) -> Option<&mut EnemySpecies> {
+        self.enemy_species
+            .get_mut(&(ENEMY_SPECIES_BANK + enemy_species::boss_species(boss) as usize))
+    }
+
+    /// Save every Enemy Species in-place.
+    pub fn save_enemy_species_to_rom(&mut self) {
+        for (species_address, species) in self.enemy_species.iter() {
+            let pc_to_write: Pc = LoRom {
+                address: *species_address,
+            }
+            .into();
+
+            self.rom.splice(
+                pc_to_write.address..pc_to_write.address + ENEMY_SPECIES_BYTE_SIZE,
+                species.to_bytes(),
+            );
+        }
+    }
+
+    /// Save every Door in-place.
+    pub fn save_doors_to_rom(&mut self) {
+        for (door_address, door) in self.doors.iter() {
+            let pc_to_write: Pc = LoRom {
+                address: *door_address,
+            }
+            .into();
+
+            self.rom.splice(
+                pc_to_write.address..pc_to_write.address + DOOR_BYTE_SIZE,
+                door.to_bytes(),
+            );
+        }
+    }
+
+    /// Save the Save Stations of every area one after another, updating the list of areas.
+    /// They are kept in-place if they still fit, otherwise moved anywhere in their bank.
+    pub fn save_save_stations_to_rom(&mut self) -> Result<(), Error> {
+        let save_stations_data = save_station::to_bytes(&self.save_stations);
+        let number_of_bytes = save_stations_data.len();
+
+        let list_address: Pc = LoRom {
+            address: SAVE_STATIONS_LIST,
+        }
+        .into();
+        let first_area = u16::from_le_bytes([
+            self.rom[list_address.address],
+            self.rom[list_address.address + 1],
+        ]) as usize;
+
+        let pc_to_write = match self.asset_sizes.get(&(SAVE_STATIONS_BANK + first_area)) {
+            Some(size) if number_of_bytes <= *size => LoRom {
+                address: SAVE_STATIONS_BANK + first_area,
+            }
+            .into(),
+            _ => {
+                self.release_asset(SAVE_STATIONS_BANK + first_area);
+                let pc_to_write = self.allocate_in_bank(
+                    Structure::SaveStation,
+                    number_of_bytes,
+                    SAVE_STATIONS_BANK,
+                )?;
+                self.asset_sizes
+                    .insert(LoRom::from(pc_to_write).address, number_of_bytes);
+                pc_to_write
+            }
+        };
+
+        self.rom.splice(
+            pc_to_write.address..pc_to_write.address + number_of_bytes,
+            save_stations_data,
+        );
+
+        // Each area points to its first Save Station.
+        let mut area_address = LoRom::from(pc_to_write).address;
+        for (area, save_stations) in self.save_stations.iter().enumerate() {
+            let pc_area_pointer = list_address.address + area * 2;
+            self.rom.splice(
+                pc_area_pointer..pc_area_pointer + 2,
+                (area_address as u16).to_le_bytes(),
+            );
+            area_address += save_stations.len() * SAVE_STATION_BYTE_SIZE;
+        }
+
+        Ok(())
+    }
+
+    /// Write 'data' of 'structure' to free space, returning the LoRom address it was written to.
+    fn write_asset(&mut self, structure: Structure, data: Vec<u8>) -> Result<usize, Error> {
+        let number_of_bytes = data.len();
+        let pc_to_write =
+            self.allocate_anywhere(number_of_bytes)
+                .map_err(|error| Error::OutOfFreeSpace {
+                    structure,
+                    size: error.size,
+                })?;
+
+        self.rom.splice(
+            pc_to_write.address..pc_to_write.address + number_of_bytes,
+            data,
+        );
+
+        let address = self.mapping.from_pc(pc_to_write);
+        self.asset_sizes.insert(address, number_of_bytes);
+        Ok(address)
+    }
+
+    /// Release the space of every asset in 'assets', then write their new data anywhere in free space.
+    /// Space for all of them is found before writing, so nothing changes if one of them doesn't fit.
+    /// Returns the address each one was written to.
+    fn rewrite_all_assets(
+        &mut self,
+        structure: Structure,
+        assets: Vec<(usize, Vec<u8>)>,
+    ) -> Result<HashMap<usize, usize>, Error> {
+        let free_space = self.free_space.clone();
+        let asset_sizes = self.asset_sizes.clone();
+        for (address, _) in assets.iter() {
+            self.release_asset(*address);
+        }
+
+        let mut pcs_to_write = Vec::new();
+        for (_, data) in assets.iter() {
+            match self.allocate_anywhere(data.len()) {
+                Ok(pc_to_write) => pcs_to_write.push(pc_to_write),
+                Err(error) => {
+                    self.free_space = free_space;
+                    self.asset_sizes = asset_sizes;
+                    return Err(Error::OutOfFreeSpace {
+                        structure,
+                        size: error.size,
+                    });
+                }
+            }
+        }
+
+        let mut remapped_addresses = HashMap::new();
+        for ((old_address, data), pc_to_write) in assets.into_iter().zip(pcs_to_write) {
+            let number_of_bytes = data.len();
+            self.rom.splice(
+                pc_to_write.address..pc_to_write.address + number_of_bytes,
+                data,
+            );
+
+            let address = self.mapping.from_pc(pc_to_write);
+            self.asset_sizes.insert(address, number_of_bytes);
+            remapped_addresses.insert(old_address, address);
+        }
+        Ok(remapped_addresses)
+    }
+
+    /// Whether the compressed asset at 'address' already decompresses to 'data'.
+    fn is_unchanged(&self, structure: Structure, address: usize, data: &[u8]) -> bool {
+        decompress_at(&self.rom, self.mapping, structure, address)
+            .is_ok_and(|(original_data, _)| original_data == data)
+    }
+
+    /// Write 'data' of 'structure' over the asset at 'address' if it fits, otherwise anywhere in free space.
+    /// Returns the address it was written to.
+    fn rewrite_asset(
+        &mut self,
+        structure: Structure,
+        address: usize,
+        data: Vec<u8>,
+    ) -> Result<usize, Error> {
+        match self.asset_sizes.get(&address) {
+            Some(size) if data.len() <= *size => {
+                let pc_to_write = self.mapping.to_pc(address);
+                self.rom
+                    .splice(pc_to_write.address..pc_to_write.address + data.len(), data);
+                Ok(address)
+            }
+            _ => {
+                self.release_asset(address);
+                self.write_asset(structure, data)
+            }
+        }
+    }
+
+    /// Write 'data' of 'structure' over the asset at 'address' if it fits, otherwise anywhere
+    /// in free space of the LoRom 'bank'. Returns the address it was written to.
+    fn rewrite_asset_in_bank(
+        &mut self,
+        structure: Structure,
+        address: usize,
+        data: Vec<u8>,
+        bank: usize,
+    ) -> Result<usize, Error> {
+        let number_of_bytes = data.len();
+        let pc_to_write = match self.asset_sizes.get(&address) {
+            Some(size) if number_of_bytes <= *size => LoRom { address }.into(),
+            _ => {
+                self.release_asset(address);
+                let pc_to_write = self.allocate_in_bank(structure, number_of_bytes, bank)?;
+                self.asset_sizes
+                    .insert(LoRom::from(pc_to_write).address, number_of_bytes);
+                pc_to_write
+            }
+        };
+
+        self.rom.splice(
+            pc_to_write.address..pc_to_write.address + number_of_bytes,
+            data,
+        );
+        Ok(LoRom::from(pc_to_write).address)
+    }
+
+    /// Allocate 'size' bytes of 'structure' anywhere in free space of the LoRom 'bank'.
+    /// Allocate 'size' bytes for an asset which can be anywhere, keeping the 'RESTRICTED_BANKS'
+    /// for the assets which can't, unless there is no other space left.
+    fn allocate_anywhere(&mut self, size: usize) -> Result<Pc, FreeSpaceError> {
+        let restricted_banks: Vec<Range<usize>> = RESTRICTED_BANKS
+            .iter()
+            .map(|bank| {
+                let bank: Pc = LoRom {
+                    address: bank + 0x8000,
+                }
+                .into();
+                bank.address..bank.address + BANK_SIZE
+            })
+            .collect();
+
+        self.free_space
+            .allocate_outside(size, &restricted_banks)
+            .or_else(|_| self.free_space.allocate(size))
+    }
+
+    fn allocate_in_bank(
+        &mut self,
+        structure: Structure,
+        size: usize,
+        bank: usize,
+    ) -> Result<Pc, Error> {
+        let bank: Pc = LoRom {
+            address: bank + 0x8000,
+        }
+        .into();
+
+        self.free_space
+            .allocate_within(size, bank.address..bank.address + BANK_SIZE)
+            .map_err(|error| Error::OutOfFreeSpace {
+                structure,
+                size: error.size,
+            })
+    }
+
+    /// Load the Level Data used by 'state', if not loaded yet.
+    fn load_level_data(&mut self, state: &State) -> Result<(), Error> {
+        let address = state.level_address as usize;
+        if self.levels.contains_key(&address) {
+            return Ok(());
+        }
+
+        let (decompressed_data, compressed_size) =
+            decompress_at(&self.rom, self.mapping, Structure::LevelData, address)?;
+        let level = level_data::load_from_bytes(
+            &decompressed_data,
+            state.layer_2_x_scroll & state.layer_2_y_scroll & 1 == 0,
+        )
+        .map_err(|error| Error::Parse {
+            structure: Structure::LevelData,
+            address,
+            error,
+        })?;
+
+        self.levels.insert(address, level);
+        self.asset_sizes.insert(address, compressed_size);
+        Ok(())
+    }
+
+    /// Load the Level Data of every State.
+    /// Failures are kept in 'diagnostics', as the remaining Rooms can still be used.
+    fn load_all_level_data(&mut self) {
+        let mut states: Vec<State> = self.states.values().copied().collect();
+        states.sort_unstable_by_key(|state| state.level_address);
+        states.dedup_by_key(|state| state.level_address);
+
+        for state in states.iter() {
+            if let Err(error) = self.load_level_data(state) {
+                self.diagnostics.push(error);
+            }
+        }
+    }
+
+    /// Give back to free space the region owned by the asset at 'address'.
+    fn release_asset(&mut self, address: usize) {
+        if let Some(number_of_bytes) = self.asset_sizes.remove(&address) {
+            self.free_space
+                .free(self.mapping.to_pc(address), number_of_bytes);
+        }
+    }
+
+    /// Load every Room reachable from 'entrances' by following their Doors,
+    /// along with all Door Lists and Doors found on the way.
+    fn load_rooms_from(&mut self, entrances: &[u16]) {
+        let mut rooms_to_visit = entrances.to_vec();
+
+        while let Some(room_pointer) = rooms_to_visit.pop() {
+            let room_address = ROOMS_BANK + room_pointer as usize;
+            if self.rooms.contains_key(&room_address) || !self.is_room(room_pointer) {
+                continue;
+            }
+
+            let room = match room::from_bytes(
+                room_pointer,
+                self.rom.offset(
+                    LoRom {
+                        address: room_address,
+                    }
+                    .into(),
+                ),
+            ) {
+                Ok(room) => room,
+                Err(error) => {
+                    self.diagnostics.push(Error::Parse {
+                        structure: Structure::Room,
+                        address: room_address,
+                        error,
+                    });
+                    continue;
+                }
+            };
+            self.asset_sizes.insert(room_address, room.to_bytes().len());
+
+            let door_list_address = ROOMS_BANK + room.doors as usize;
+            if !self.door_lists.contains_key(&door_list_address) {
+                let source = self.rom.offset(
+                    LoRom {
+                        address: door_list_address,
+                    }
+                    .into(),
+                );
+                let number_of_doors =
+                    door_list::count_doors(source, |door_pointer| self.is_door(door_pointer));
+                let door_list = door_list::load_bytes(number_of_doors, source);
+
+                for door_pointer in door_list.iter() {
+                    let door_address = DOORS_BANK + *door_pointer as usize;
+                    let door = door::load_bytes(
+                        1,
+                        self.rom.offset(
+                            LoRom {
+                                address: door_address,
+                            }
+                            .into(),
+                        ),
+                    )
+                    .remove(0);
+
+                    // Elevator pads have doors without destination.
+                    if door.destination_room != 0 {
+                        rooms_to_visit.push(door.destination_room);
+                    }
+                    self.doors.insert(door_address, door);
+                }
+
+                self.asset_sizes
+                    .insert(door_list_address, number_of_doors * 2);
+                self.door_lists.insert(door_list_address, door_list);
+            }
+
+            self.rooms.insert(room_address, room);
+        }
+    }
+
+    fn is_room(&self, room_pointer: u16) -> bool {
+        let pc: Pc = LoRom {
+            address: ROOMS_BANK + room_pointer as usize,
+        }
+        .into();
+
+        room_pointer >= 0x8000
+            && self
+                .rom
+                .get(pc.address..)
+                .is_some_and(|source| room::is_valid_header(source, NUMBER_OF_AREAS))
+    }
+
+    fn is_door(&self, door_pointer: u16) -> bool {
+        let pc: Pc = LoRom {
+            address: DOORS_BANK + door_pointer as usize,
+        }
+        .into();
+
+        door_pointer >= 0x8000
+            && self
+                .rom
+                .get(pc.address..pc.address + DOOR_BYTE_SIZE)
+                .is_some_and(|source| {
+                    let destination_room = u16::from_le_bytes([source[0], source[1]]);
+                    destination_room == 0 || self.is_room(destination_room)
+                })
+    }
+
+    /// Register the known free space regions which are still unused.
+    /// Free space is filled with $FF, so only the trailing run of $FF of each region is taken.
+    /// When this run doesn't start the region, its first byte may be the end of compressed data.
+    fn load_free_space(&mut self) {
+        for (start, end) in FREE_SPACE {
+            let start: Pc = LoRom { address: *start }.into();
+            let end: Pc = LoRom { address: *end }.into();
+
+            if let Some(region) = self.rom.get(start.address..=end.address) {
+                let used = region
+                    .iter()
+                    .rposition(|byte| *byte != 0xFF)
+                    .map_or(0, |last_used_byte| last_used_byte + 2);
+
+                self.free_space.free(
+                    Pc {
+                        address: start.address + used,
+                    },
+                    region.len().saturating_sub(used),
+                );
+            }
+        }
+    }
+
+    fn check_md5(&self) -> bool {
+        md5::compute(&self.rom).0 == UNHEADERED_MD5
+    }
+
+    /// Check the internal header is from Super Metroid and detect which version it is.
+    fn detect_version(&self) -> Result<RomVersion, Error> {
+        if !self.header.title().eq_ignore_ascii_case(TITLE)
+            || self.header.mapping().is_none()
+            || !self.header.has_valid_complement()
+        {
+            return Err(header_error(ParseError::BadHeader));
+        }
+
+        Ok(if self.check_md5() {
+            RomVersion::JapanUsa
+        } else if self.header.is_pal() {
+            RomVersion::Pal
+        } else {
+            RomVersion::ModifiedJapanUsa
+        })
+    }
+}
+
+/// Decompress the 'structure' at 'address' of 'rom', along with how many bytes it occupies.
+fn decompress_at(
+    rom: &[u8],
+    mapping: Mapping,
+    structure: Structure,
+    address: usize,
+) -> Result<(Vec<u8>, usize), Error> {
+    let source = rom
+        .get(mapping.to_pc(address).address..)
+        .unwrap_or_default();
+    lz5_decompress_with_size(source).map_err(|error| Error::Lz5 {
+        structure,
+        address,
+        error,
+    })
+}
+
+/// Failure to parse the internal header at $00:FFC0.
+fn header_error(error: ParseError) -> Error {
+    Error::Parse {
+        structure: Structure::Header,
+        address: LoRom::from(Pc {
+            address: header::LO_ROM_HEADER,
+        })
+        .address,
+        error,
+    }
+}
+
+/// Load only the vanilla (JU) ROM.
+pub fn load_unheadered_rom(data: Vec<u8>) -> Result<SuperMetroid, Error> {
+    load_rom(
+        data,
+        LoadOptions {
+            vanilla_only: true,
+            ..Default::default()
+        },
+    )
+}
+
+/// Load any ROM with a valid Super Metroid header, including modified ones.
+/// Data tables are expected at the same addresses as the JU version, so only LoRom images are accepted.
+/// A copier header is stripped before parsing and kept in 'copier_header'.
+pub fn load_rom(data: Vec<u8>, options: LoadOptions) -> Result<SuperMetroid, Error> {
+    let (copier_header, original_rom) = header::strip_copier_header(data);
+
+    let mut rom = original_rom.clone();
+    for patch in options.patches.iter() {
+        rom = crate::patch::apply(&rom, patch).map_err(|error| Error::Patch { error })?;
+    }
+
+    let mut sm = SuperMetroid {
+        header: header::from_rom(&rom).map_err(header_error)?,
+        rom,
+        original_rom,
+        copier_header,
+        ..Default::default()
+    };
+
+    sm.version = sm.detect_version()?;
+    sm.mapping = sm.header.mapping().unwrap_or_default();
+    // Data tables are read and written at LoRom addresses, which an expanded LoRom keeps.
+    if !matches!(sm.mapping, Mapping::LoRom | Mapping::ExLoRom) {
+        return Err(Error::UnsupportedMapping {
+            mapping: sm.mapping,
+        });
+    }
+
+    // A modified ROM may have a stale checksum, which doesn't stop it from being loaded.
+    if !sm.has_valid_checksum() {
+        sm.diagnostics.push(Error::BadChecksum {
+            header: sm.header.checksum,
+            computed: header::checksum(&sm.rom),
+        });
+    }
+    if options.vanilla_only && sm.version != RomVersion::JapanUsa {
+        return Err(Error::NotVanilla);
+    }
+
+    // Load all Tilesets.
+    let tilesets = sm
+        .rom
+        .offset(LoRom { address: TILESETS }.into())
+        .get(..tileset::TILESET_DATA_SIZE * tileset::NUMBER_OF_TILESETS)
+        .ok_or(Error::Parse {
+            structure: Structure::Tileset,
+            address: TILESETS,
+            error: ParseError::Truncated,
+        })?;
+    sm.tilesets = tileset::from_bytes(tilesets);
+    // Load all Tilesets.
+    for tileset in sm.tilesets.iter() {
+        // Load it's Palette.
+        if let Entry::Vacant(entry) = sm.palettes.entry(tileset.palette as usize) {
+            let address = tileset.palette as usize;
+            let (decompressed_data, compressed_size) =
+                decompress_at(&sm.rom, sm.mapping, Structure::Palette, address)?;
+            entry.insert(palette::from_bytes(&decompressed_data).map_err(|error| {
+                Error::Parse {
+                    structure: Structure::Palette,
+                    address,
+                    error,
+                }
+            })?);
+            sm.asset_sizes.insert(address, compressed_size);
+        }
+
+        // Load it's Graphics.
+        if let Entry::Vacant(entry) = sm.graphics.entry(tileset.graphic as usize) {
+            let address = tileset.graphic as usize;
+            let (decompressed_data, compressed_size) =
+                decompress_at(&sm.rom, sm.mapping, Structure::Gfx, address)?;
+            entry.insert(gfx::from_4bpp(&decompressed_data));
+            sm.asset_sizes.insert(address, compressed_size);
+        }
+
+        // Load all Tile Tables.
+        if let Entry::Vacant(entry) = sm.tile_tables.entry(tileset.tile_table as usize) {
+            let address = tileset.tile_table as usize;
+            let (decompressed_data, compressed_size) =
+                decompress_at(&sm.rom, sm.mapping, Structure::TileTable, address)?;
+            entry.insert(tile_table::from_bytes(&decompressed_data));
+            sm.asset_sizes.insert(address, compressed_size);
+        }
+    }
+
+    // Load CRE graphic.
+    let (decompressed_data, compressed_size) =
+        decompress_at(&sm.rom, sm.mapping, Structure::Gfx, CRE_GFX)?;
+    sm.cre_gfx = gfx::from_4bpp(&decompressed_data);
+    sm.asset_sizes.insert(CRE_GFX, compressed_size);
+
+    // Load CRE tileset.
+    let (decompressed_data, compressed_size) =
+        decompress_at(&sm.rom, sm.mapping, Structure::TileTable, CRE_TILESET)?;
+    sm.cre_tileset = tile_table::from_bytes(&decompressed_data);
+    sm.asset_sizes.insert(CRE_TILESET, compressed_size);
+
+    // Load all Save Stations, starting from the first area in the list.
+    let save_stations_error = |error| Error::Parse {
+        structure: Structure::SaveStation,
+        address: SAVE_STATIONS_LIST,
+        error,
+    };
+    let save_stations_list = sm.rom.offset(
+        LoRom {
+            address: SAVE_STATIONS_LIST,
+        }
+        .into(),
+    );
+    let first_area = match save_stations_list {
+        [low, high, ..] => u16::from_le_bytes([*low, *high]) as usize,
+        _ => return Err(save_stations_error(ParseError::Truncated)),
+    };
+    sm.save_stations = save_station::load_all_from_list(
+        sm.rom.offset(
+            LoRom {
+                address: SAVE_STATIONS_BANK + first_area,
+            }
+            .into(),
+        ),
+        save_stations_list,
+        NUMBER_OF_AREAS,
+    )
+    .map_err(save_stations_error)?;
+    let save_stations_size = save_station::to_bytes(&sm.save_stations).len();
+    sm.asset_sizes
+        .insert(SAVE_STATIONS_BANK + first_area, save_stations_size);
+
+    // Load all Rooms, Door Lists and Doors reachable from where a game can start.
+    let mut entrances = ENTRANCES.to_vec();
+    entrances.extend(
+        sm.save_stations
+            .iter()
+            .flatten()
+            .map(|save_station| save_station.room_pointer)
+            .filter(|room_pointer| *room_pointer != 0),
+    );
+    sm.load_rooms_from(&entrances);
+
+    sm.load_states();
+    sm.load_all_level_data();
+    sm.load_plm_populations();
+    sm.load_enemies();
+    sm.load_enemy_species();
+    sm.load_fx();
+
+    sm.load_free_space();
+    // The terminator of the Save Stations is made of $FF bytes, which look like free space.
+    sm.free_space.reserve(
+        LoRom {
+            address: SAVE_STATIONS_BANK + first_area,
+        }
+        .into(),
+        save_stations_size,
+    );
+
+    Ok(sm)
+}
+
+pub trait Offset {
+    fn offset(&self, start: Pc) -> &[u8];
+}
+
+impl Offset for Vec<u8> {
+    fn offset(&self, start: Pc) -> &[u8] {
+        self.get(start.address..).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        enemy::{Enemy, EnemySetEntry},
+        enemy_species::NAME_SIZE,
+        fx::{Fx, FxType},
+        plm::Plm,
+        room::{Condition, StateCondition},
+        tile_table::Tile,
+        *,
+    };
+    use crate::{compress::Lz5Error, patch::PatchError};
+
+    /// Load Super Metroid data from rom.
+    #[test]
+    #[ignore = "Requires ROM data to run, which is Copyrighted."]
+    fn load_super_metroid_data_from_rom() {
+        assert!(load_unheadered_rom(
+            fs::read("/home/rondao/dev/snes_data/test/Super Metroid (JU) [!].smc").unwrap()
+        )
+        .is_ok());
+    }
+
+    /// Fail to load Super Metroid data from incorrect rom data.
+    #[test]
+    #[ignore = "TODO: Create generic file to represent incorrect ROM data."]
+    fn load_super_metroid_data_from_incorrect_rom() {
+        assert!(load_unheadered_rom(
+            fs::read("/home/rondao/dev/snes_data/test/Incorrect Super Metroid.smc").unwrap()
+        )
+        .is_err());
+    }
+
+    /// Detect the version of a ROM from its internal header.
+    #[test]
+    fn detect_rom_version_from_header() {
+        let mut header = Header {
+            map_mode: 0x30,
+            checksum_complement: 0x0720,
+            checksum: 0xF8DF,
+            ..Default::default()
+        };
+        header.title[..TITLE.len()].copy_from_slice(TITLE.as_bytes());
+
+        let mut sm = SuperMetroid {
+            header: header.clone(),
+            ..Default::default()
+        };
+        assert_eq!(sm.detect_version().unwrap(), RomVersion::ModifiedJapanUsa);
+
+        sm.header.destination = 0x02;
+        assert_eq!(sm.detect_version().unwrap(), RomVersion::Pal);
+
+        sm.header = Header {
+            map_mode: 0x31,
+            ..header.clone()
+        };
+        assert_eq!(sm.detect_version().unwrap(), RomVersion::ModifiedJapanUsa);
+
+        sm.header = Header {
+            map_mode: 0x23,
+            ..header.clone()
+        };
+        assert!(sm.detect_version().is_err());
+
+        sm.header = Header {
+            checksum: 0x0000,
+            ..header.clone()
+        };
+        assert!(sm.detect_version().is_err());
+
+        sm.header = Header {
+            title: [b' '; header::TITLE_SIZE],
+            ..header
+        };
+        assert!(sm.detect_version().is_err());
+    }
+
+    /// Fail to load a modified ROM when only vanilla is accepted.
+    #[test]
+    fn load_modified_rom_with_vanilla_only() {
+        let mut rom = vec![0; 0x8000];
+        rom[header::LO_ROM_HEADER..header::LO_ROM_HEADER + TITLE.len()]
+            .copy_from_slice(TITLE.as_bytes());
+        rom[header::LO_ROM_HEADER + 21] = 0x30;
+        rom[header::LO_ROM_HEADER + 28..header::LO_ROM_HEADER + 32]
+            .copy_from_slice(&[0x20, 0x07, 0xDF, 0xF8]);
+
+        assert_eq!(
+            load_rom(
+                rom,
+                LoadOptions {
+                    vanilla_only: true,
+                    ..Default::default()
+                }
+            )
+            .unwrap_err(),
+            Error::NotVanilla
+        );
+    }
+
+    /// Find the header of HiRom and ExHiRom images, but refuse to load them with a clear error,
+    /// since the data tables of the game are only found at LoRom addresses.
+    #[test]
+    fn reject_hi_rom_and_ex_hi_rom() {
+        for (position, map_mode, mapping) in [
+            (header::HI_ROM_HEADER, 0x31, Mapping::HiRom),
+            (header::EX_HI_ROM_HEADER, 0x35, Mapping::ExHiRom),
+        ] {
+            let mut rom = vec![0; position + BANK_SIZE];
+            rom[position..position + TITLE.len()].copy_from_slice(TITLE.as_bytes());
+            rom[position + 21] = map_mode;
+            rom[position + 28..position + 32].copy_from_slice(&[0x20, 0x07, 0xDF, 0xF8]);
+
+            assert_eq!(header::from_rom(&rom).unwrap().mapping(), Some(mapping));
+            assert_eq!(
+                load_rom(rom, LoadOptions::default()).unwrap_err(),
+                Error::UnsupportedMapping { mapping }
+            );
+        }
+    }
+
+    /// Fail to load a ROM cut short after its header instead of reading past its end.
+    #[test]
+    fn load_truncated_rom() {
+        let mut rom = vec![0; 0x8000];
+        rom[header::LO_ROM_HEADER..header::LO_ROM_HEADER + TITLE.len()]
+            .copy_from_slice(TITLE.as_bytes());
+        rom[header::LO_ROM_HEADER + 21] = 0x30;
+        rom[header::LO_ROM_HEADER + 28..header::LO_ROM_HEADER + 32]
+            .copy_from_slice(&[0x20, 0x07, 0xDF, 0xF8]);
+
+        assert_eq!(
+            load_rom(rom, LoadOptions::default()).unwrap_err(),
+            Error::Parse {
+                structure: Structure::Tileset,
+                address: TILESETS,
+                error: ParseError::Truncated,
+            }
+        );
+    }
+
+    /// Restore the copier header only when asked to and when the ROM was loaded with one.
+    #[test]
+    fn save_to_file_with_copier_header() {
+        let filename = std::env::temp_dir().join("zen_save_to_file_with_copier_header.smc");
+        let filename = filename.to_str().unwrap();
+        let mut sm = SuperMetroid {
+            rom: vec![0; 0x8000],
+            ..Default::default()
+        };
+
+        sm.save_to_file(filename, true).unwrap();
+        assert_eq!(fs::read(filename).unwrap(), sm.rom);
+
+        sm.copier_header = Some(vec![0xAA; header::COPIER_HEADER_SIZE]);
+        sm.save_to_file(filename, false).unwrap();
+        assert_eq!(fs::read(filename).unwrap(), sm.rom);
+
+        sm.save_to_file(filename, true).unwrap();
+        let saved = fs::read(filename).unwrap();
+        assert_eq!(
+            header::strip_copier_header(saved),
+            (sm.copier_header.clone(), sm.rom.clone())
+        );
+        fs::remove_file(filename).unwrap();
+    }
+
+    /// Apply patches before parsing, failing on those made for another ROM.
+    #[test]
+    fn load_rom_with_patches() {
+        let original_rom = vec![0; 0x8000];
+        let mut patched_rom = original_rom.clone();
+        patched_rom[header::LO_ROM_HEADER..header::LO_ROM_HEADER + TITLE.len()]
+            .copy_from_slice(TITLE.as_bytes());
+        patched_rom[header::LO_ROM_HEADER + 21] = 0x30;
+        patched_rom[header::LO_ROM_HEADER + 28..header::LO_ROM_HEADER + 32]
+            .copy_from_slice(&[0x20, 0x07, 0xDF, 0xF8]);
+
+        let options = |patches| LoadOptions {
+            vanilla_only: true,
+            patches,
+        };
+        assert!(matches!(
+            load_rom(original_rom.clone(), options(vec![])).unwrap_err(),
+            Error::Parse {
+                structure: Structure::Header,
+                ..
+            }
+        ));
+        assert_eq!(
+            load_rom(
+                original_rom.clone(),
+                options(vec![ips::create(&original_rom, &patched_rom)])
+            )
+            .unwrap_err(),
+            Error::NotVanilla
+        );
+        assert_eq!(
+            load_rom(
+                patched_rom.clone(),
+                options(vec![bps::create(&original_rom, &patched_rom)])
+            )
+            .unwrap_err(),
+            Error::Patch {
+                error: PatchError::BadChecksum
+            }
+        );
+    }
+
+    /// Expand the ROM to 4 MB and then into an ExLoRom, writing assets into the new banks.
+    #[test]
+    fn expand_rom_into_ex_lo_rom() {
+        let mut sm = SuperMetroid {
+            rom: vec![0x00; 0x30_0000],
+            ..Default::default()
+        };
+        sm.header.map_mode = 0x30;
+        sm.header.rom_size = 0x0C;
+
+        assert_eq!(
+            sm.expand(0x20_0000),
+            Err(Error::InvalidRomSize { size: 0x20_0000 })
+        );
+        assert!(sm.expand(0x40_0100).is_err());
+
+        sm.expand(MAX_LO_ROM_SIZE).unwrap();
+        assert_eq!(sm.rom.len(), MAX_LO_ROM_SIZE);
+        assert_eq!(sm.free_space.regions(), [0x30_0000..MAX_LO_ROM_SIZE]);
+        assert_eq!(sm.mapping, Mapping::LoRom);
+
+        sm.free_space = FreeSpace::default();
+        sm.expand(0x60_0000).unwrap();
+        assert_eq!(sm.mapping, Mapping::ExLoRom);
+        assert_eq!(sm.header.rom_size, 0x0D);
+        assert_eq!(
+            sm.free_space.regions(),
+            [MAX_LO_ROM_SIZE + BANK_SIZE..0x60_0000]
+        );
+        assert_eq!(
+            sm.write_asset(Structure::LevelData, vec![0x12; 0x10])
+                .unwrap(),
+            0x01_8000
+        );
+
+        sm.update_header().unwrap();
+        for position in [header::LO_ROM_HEADER, header::EX_LO_ROM_HEADER] {
+            let header = header::from_bytes(&sm.rom[position..]).unwrap();
+            assert!(header.has_valid_complement());
+            assert_eq!(header.mapping(), Some(Mapping::ExLoRom));
+        }
+        assert!(sm.has_valid_checksum());
+
+        sm.rom[0] = 0xFF;
+        assert!(!sm.has_valid_checksum());
+
+        // Without its expansion past 4 MB, an ExLoRom has no room for its header.
+        sm.rom.truncate(MAX_LO_ROM_SIZE);
+        assert_eq!(
+            sm.update_header(),
+            Err(Error::InvalidRomSize {
+                size: MAX_LO_ROM_SIZE
+            })
+        );
+        sm.mapping = Mapping::LoRom;
+        sm.rom.truncate(header::LO_ROM_HEADER);
+        assert!(sm.update_header().is_err());
+    }
+
+    /// Keep Level Data failures as diagnostics, telling where and why they failed.
+    #[test]
+    fn load_level_data_with_diagnostics() {
+        let mut sm = SuperMetroid {
+            // Empty data, then a direct copy missing its bytes.
+            rom: vec![0xFF, 0x02, 0x00],
+            ..Default::default()
+        };
+        for (address, level_address) in [(0x0000, 0x80_8000), (0x001A, 0x80_8001)] {
+            sm.states.insert(
+                address,
+                State {
+                    level_address,
+                    ..Default::default()
+                },
+            );
+        }
+
+        sm.load_all_level_data();
+        assert!(sm.levels.is_empty());
+        assert_eq!(
+            sm.diagnostics,
+            [
+                Error::Parse {
+                    structure: Structure::LevelData,
+                    address: 0x80_8000,
+                    error: ParseError::Truncated,
+                },
+                Error::Lz5 {
+                    structure: Structure::LevelData,
+                    address: 0x80_8001,
+                    error: Lz5Error::Truncated,
+                },
+            ]
+        );
+    }
+
+    /// Keep States cut short by the end of the ROM as diagnostics.
+    #[test]
+    fn load_states_with_diagnostics() {
+        let mut sm = SuperMetroid {
+            rom: vec![0x00; 0x80000],
+            rooms: HashMap::from([(
+                0x8F91F8,
+                Room {
+                    state_conditions: vec![
+                        StateCondition {
+                            condition: Condition::MorphBall,
+                            state_address: 0xFFF0,
+                        },
+                        StateCondition {
+                            condition: Condition::Default,
+                            state_address: 0x9213,
+                        },
+                    ],
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+
+        sm.load_states();
+        assert_eq!(sm.states.keys().collect::<Vec<_>>(), [&0x9213]);
+        assert_eq!(sm.asset_sizes, HashMap::from([(0x8F9213, STATE_BYTE_SIZE)]));
+        assert_eq!(
+            sm.diagnostics,
+            [Error::Parse {
+                structure: Structure::State,
+                address: 0x8FFFF0,
+                error: ParseError::Truncated,
+            }]
+        );
+    }
+
+    /// Save palettes into the space released by the old ones, and fail when there is none.
+    #[test]
+    fn save_palettes_into_free_space() {
+        let mut sm = SuperMetroid {
+            rom: vec![0xFF; 0x8000],
+            palettes: HashMap::from([(0x808010, Palette::default())]),
+            tilesets: vec![Tileset {
+                palette: 0x808010,
+                ..Default::default()
+            }],
+            asset_sizes: HashMap::from([(0x808010, 0x40)]),
+            ..Default::default()
+        };
+        sm.free_space.free(Pc { address: 0x00 }, 0x08);
+
+        let remapped_addresses = sm.save_palettes_to_rom().unwrap();
+        assert_eq!(remapped_addresses, HashMap::from([(0x808010, 0x808000)]));
+        assert_eq!(sm.tilesets[0].palette, 0x808000);
+        assert_eq!(sm.asset_sizes, HashMap::from([(0x808000, 4)]));
+        assert_eq!(&sm.rom[..4], [0xE4, 0xFF, 0x00, 0xFF]);
+        assert_eq!(sm.free_space.regions(), [0x04..0x08, 0x10..0x50]);
+
+        // Unchanged, it's not written again even without any free space.
+        sm.free_space = FreeSpace::default();
+        sm.asset_sizes.clear();
+        assert!(sm.save_palettes_to_rom().unwrap().is_empty());
+
+        sm.palettes.get_mut(&0x808000).unwrap().sub_palettes[0].colors[0].r = 0x1F;
+        assert!(sm.save_palettes_to_rom().is_err());
+    }
+
+    /// Run out of free space after the first of two level data, leaving the ROM untouched.
+    #[test]
+    fn save_level_data_out_of_free_space() {
+        let level = LevelData {
+            layer1: vec![Default::default(); 4],
+            ..Default::default()
+        };
+        let level_size = lz5_compress(&level.to_bytes()).len();
+        // Only the space released by the first level data is free.
+        let mut sm = SuperMetroid {
+            rom: vec![0xFF; 0x8000],
+            levels: HashMap::from([(0x808000, level.clone()), (0x808100, level)]),
+            asset_sizes: HashMap::from([(0x808000, level_size)]),
+            ..Default::default()
+        };
+
+        let rom = sm.rom.clone();
+        let free_space = sm.free_space.clone();
+        let asset_sizes = sm.asset_sizes.clone();
+        assert!(matches!(
+            sm.save_level_data_to_rom(),
+            Err(Error::OutOfFreeSpace {
+                structure: Structure::LevelData,
+                ..
+            })
+        ));
+        assert_eq!(sm.rom, rom);
+        assert_eq!(sm.free_space, free_space);
+        assert_eq!(sm.asset_sizes, asset_sizes);
+        assert_eq!(sm.levels.len(), 2);
+        assert!(sm.levels.contains_key(&0x808000));
+    }
+
+    /// Relocate level data out of the banks kept for Rooms and the like, while it has space elsewhere.
+    #[test]
+    fn save_level_data_outside_restricted_banks() {
+        let mut sm = SuperMetroid {
+            rom: vec![0xFF; 0x10000],
+            levels: HashMap::from([(
+                0x808100,
+                LevelData {
+                    layer1: vec![Default::default(); 4],
+                    ..Default::default()
+                },
+            )]),
+            states: HashMap::from([(
+                0x91F8,
+                State {
+                    level_address: 0x808100,
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+        sm.free_space.free(Pc { address: 0x0000 }, 0x40);
+        sm.free_space.free(Pc { address: 0x8000 }, 0x40);
+
+        assert_eq!(
+            sm.save_level_data_to_rom().unwrap(),
+            HashMap::from([(0x808100, 0x818000)])
+        );
+        assert_eq!(sm.states[&0x91F8].level_address, 0x818000);
+        assert_eq!(sm.free_space.regions()[0], 0x0000..0x0040);
+
+        // Unchanged, it stays where it is.
+        assert!(sm.save_level_data_to_rom().unwrap().is_empty());
+    }
+
+    /// Save Gfx in-place while they fit, and relocate them with their Tilesets when they grow.
+    #[test]
+    fn save_grown_graphics_into_free_space() {
+        let mut sm = SuperMetroid {
+            rom: vec![0xFF; 0x8000],
+            graphics: HashMap::from([(
+                0x808010,
+                Gfx {
+                    tiles: vec![TileGfx { colors: [0; 64] }; 4],
+                },
+            )]),
+            tilesets: vec![Tileset {
+                graphic: 0x808010,
+                ..Default::default()
+            }],
+            asset_sizes: HashMap::from([(0x808010, 0x02)]),
+            ..Default::default()
+        };
+        sm.free_space.free(Pc { address: 0x20 }, 0x20);
+
+        let remapped_addresses = sm.save_graphics_to_rom().unwrap();
+        assert_eq!(remapped_addresses, HashMap::from([(0x808010, 0x808020)]));
+        assert_eq!(sm.tilesets[0].graphic, 0x808020);
+        assert!(sm.graphics.contains_key(&0x808020));
+        assert_eq!(&sm.rom[0x20..0x24], [0xE4, 0x7F, 0x00, 0xFF]);
+
+        // Unchanged, it's not written again even if it would not fit.
+        sm.asset_sizes.insert(0x808020, 0x00);
+        assert!(sm.save_graphics_to_rom().unwrap().is_empty());
+        assert_eq!(sm.tilesets[0].graphic, 0x808020);
+
+        // It fits now, so it's saved at the same address.
+        sm.asset_sizes.insert(0x808020, 0x04);
+        sm.graphics.get_mut(&0x808020).unwrap().tiles[0].colors[0] = 1;
+        assert!(sm.save_graphics_to_rom().unwrap().is_empty());
+        assert_eq!(sm.tilesets[0].graphic, 0x808020);
+        assert_ne!(&sm.rom[0x20..0x24], [0xE4, 0x7F, 0x00, 0xFF]);
+    }
+
+    /// Save the CRE Tileset in-place only when it changed, and fail when it grew bigger.
+    #[test]
+    fn save_cre_in_place() {
+        let mut sm = SuperMetroid {
+            rom: vec![0xFF; 0x400000],
+            cre_gfx: Gfx {
+                tiles: vec![TileGfx { colors: [0; 64] }; 4],
+            },
+            cre_tileset: vec![Tile::default(); 4],
+            ..Default::default()
+        };
+        let cre_gfx: Pc = LoRom { address: CRE_GFX }.into();
+        let cre_gfx_bytes = lz5_compress(&sm.cre_gfx.to_4bpp());
+        sm.rom[cre_gfx.address..cre_gfx.address + cre_gfx_bytes.len()]
+            .copy_from_slice(&cre_gfx_bytes);
+        let cre_tileset: Pc = LoRom {
+            address: CRE_TILESET,
+        }
+        .into();
+        sm.rom[cre_tileset.address..cre_tileset.address + 3].copy_from_slice(&[0x27, 0x00, 0xFF]);
+        sm.asset_sizes = HashMap::from([(CRE_GFX, 0), (CRE_TILESET, 0x10)]);
+
+        sm.save_cre_to_rom().unwrap();
+        assert_eq!(
+            &sm.rom[cre_tileset.address..cre_tileset.address + 4],
+            [0x27, 0x00, 0xFF, 0xFF]
+        );
+
+        sm.cre_tileset[1].gfx_index = 0x0234;
+        sm.save_cre_to_rom().unwrap();
+        assert_eq!(
+            decompress_at(&sm.rom, sm.mapping, Structure::TileTable, CRE_TILESET)
+                .unwrap()
+                .0,
+            [0x00, 0x00, 0x34, 0x02, 0x00, 0x00, 0x00, 0x00]
+        );
+
+        sm.cre_tileset = (0..0x20)
+            .map(|gfx_index| Tile {
+                gfx_index,
+                ..Default::default()
+            })
+            .collect();
+        assert!(matches!(
+            sm.save_cre_to_rom(),
+            Err(Error::OutOfFreeSpace {
+                structure: Structure::TileTable,
+                ..
+            })
+        ));
+    }
+
+    /// Discover Rooms by following the Doors of their Door Lists.
+    #[test]
+    fn load_rooms_by_walking_doors() {
+        let mut sm = SuperMetroid {
+            rom: vec![0; 0x80000],
+            ..Default::default()
+        };
+        let mut write = |address: usize, data: &[u8]| {
+            let pc: Pc = LoRom { address }.into();
+            sm.rom[pc.address..pc.address + data.len()].copy_from_slice(data);
+        };
+
+        #[rustfmt::skip]
+        write(0x8F8000, &[0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x70, 0xA0, 0x00, 0x00, 0x81, 0xE6, 0xE5]);
+        write(0x8F8100, &[0x00, 0x88, 0x18, 0x88, 0x00, 0x00]);
+        #[rustfmt::skip]
+        write(0x8F8200, &[0x01, 0x00, 0x00, 0x00, 0x01, 0x01, 0x70, 0xA0, 0x00, 0x00, 0x83, 0xE6, 0xE5]);
+        write(0x8F8300, &[0x0C, 0x88]);
+
+        // Doors to the second Room, to the first one, and an elevator pad.
+        write(0x838800, &[0x00, 0x82]);
+        write(0x83880C, &[0x00, 0x80]);
+        write(0x838818, &[0x00, 0x00]);
+
+        sm.load_rooms_from(&[0x8000]);
+
+        let mut rooms: Vec<usize> = sm.rooms.keys().copied().collect();
+        rooms.sort_unstable();
+        assert_eq!(rooms, [0x8F8000, 0x8F8200]);
+
+        assert_eq!(sm.door_lists[&0x8F8100], vec![0x8800, 0x8818]);
+        assert_eq!(sm.door_lists[&0x8F8300], vec![0x880C]);
+        assert_eq!(sm.doors.len(), 3);
+        assert_eq!(sm.doors[&0x83880C].destination_room, 0x8000);
+        assert_eq!(sm.asset_sizes[&0x8F8100], 4);
+    }
+
+    /// Relocate a Room which grew bigger than its space, with all pointers to it.
+    #[test]
+    fn save_grown_room_into_free_space() {
+        #[rustfmt::skip]
+        let room_data = [
+            0x00, 0x01, 0x02, 0x03, 0x01, 0x01, 0x70, 0xA0, 0x00, // Room header
+            0x7B, 0x92,                                           // Doors
+            0xE6, 0xE5,                                           // State condition terminator
+        ];
+        let mut room = room::from_bytes(0x8000, &room_data).unwrap();
+        room.state_conditions.push(StateCondition {
+            condition: Condition::MorphAndMissiles,
+            state_address: 0x9000,
+        });
+
+        let mut sm = SuperMetroid {
+            rom: vec![0xFF; 0x80000],
+            rooms: HashMap::from([(0x8F8000, room)]),
+            states: HashMap::from([
+                (0x800D, State::default()),
+                (
+                    0x9000,
+                    State {
+                        tileset: 0x05,
+                        ..Default::default()
+                    },
+                ),
+            ]),
+            doors: HashMap::from([(
+                0x8388FE,
+                Door {
+                    destination_room: 0x8000,
+                    ..Default::default()
+                },
+            )]),
+            save_stations: vec![vec![SaveStation {
+                room_pointer: 0x8000,
+                ..Default::default()
+            }]],
+            asset_sizes: HashMap::from([(0x8F8000, 13), (0x8F800D, STATE_BYTE_SIZE)]),
+            ..Default::default()
+        };
+        sm.free_space
+            .free(LoRom { address: 0x8FE000 }.into(), 0x100);
+
+        let remapped_addresses = sm.save_rooms_to_rom().unwrap();
+        assert_eq!(remapped_addresses, HashMap::from([(0x8F8000, 0x8FE000)]));
+
+        let room = sm.rooms[&0x8FE000].clone();
+        assert_eq!(room.state_conditions[0].state_address, 0xE011);
+        assert_eq!(room.state_conditions[1].state_address, 0xE02B);
+        assert_eq!(sm.states[&0xE02B].tileset, 0x05);
+        assert_eq!(sm.doors[&0x8388FE].destination_room, 0xE000);
+        assert_eq!(sm.save_stations[0][0].room_pointer, 0xE000);
+
+        let room_address: Pc = LoRom { address: 0x8FE000 }.into();
+        assert_eq!(
+            room::from_bytes(0xE000, sm.rom.offset(room_address)).unwrap(),
+            room
+        );
+        assert_eq!(
+            state::load_bytes(&sm.rom.offset(LoRom { address: 0x8FE02B }.into())).unwrap(),
+            sm.states[&0xE02B]
+        );
+
+        // The old space of the Room and its State is free again.
+        assert_eq!(sm.free_space.regions()[0], 0x78000..0x78027);
+
+        // Unchanged, or with only a State changed, the Room keeps its address.
+        assert!(sm.save_rooms_to_rom().unwrap().is_empty());
+        sm.states.get_mut(&0xE02B).unwrap().tileset = 0x06;
+        assert!(sm.save_rooms_to_rom().unwrap().is_empty());
+        assert_eq!(sm.rooms[&0x8FE000], room);
+        assert_eq!(
+            state::load_bytes(&sm.rom.offset(LoRom { address: 0x8FE02B }.into()))
+                .unwrap()
+                .tileset,
+            0x06
+        );
+        assert_eq!(sm.free_space.regions()[0], 0x78000..0x78027);
+    }
+
+    /// Relocate a Door List which grew bigger, updating the Room pointing to it.
+    #[test]
+    fn save_grown_door_list_into_free_space() {
+        let mut sm = SuperMetroid {
+            rom: vec![0; 0x80000],
+            rooms: HashMap::from([(
+                0x8F8000,
+                Room {
+                    doors: 0x9000,
+                    ..Default::default()
+                },
+            )]),
+            door_lists: HashMap::from([(0x8F9000, vec![0x88FE, 0x890A])]),
+            asset_sizes: HashMap::from([(0x8F9000, 2)]),
+            ..Default::default()
+        };
+        sm.free_space
+            .free(LoRom { address: 0x8FE000 }.into(), 0x100);
+
+        let remapped_addresses = sm.save_door_lists_to_rom().unwrap();
+        assert_eq!(remapped_addresses, HashMap::from([(0x8F9000, 0x8FE000)]));
+        assert_eq!(sm.rooms[&0x8F8000].doors, 0xE000);
+        assert_eq!(sm.door_lists[&0x8FE000], vec![0x88FE, 0x890A]);
+
+        let door_list_address: Pc = LoRom { address: 0x8FE000 }.into();
+        assert_eq!(
+            &sm.rom[door_list_address.address..door_list_address.address + 4],
+            [0xFE, 0x88, 0x0A, 0x89]
+        );
+    }
+
+    /// Load what each State points to, and relocate it when it grows, even into the space
+    /// released by another one.
+    #[test]
+    fn save_grown_state_assets_into_free_space() {
+        let mut sm = SuperMetroid {
+            rom: vec![0; 0x200000],
+            states: HashMap::from([
+                (
+                    0x91F8,
+                    State {
+                        plm_population: 0x9000,
+                        enemy_population: 0x8000,
+                        enemy_set: 0x8000,
+                        fx: 0x8000,
+                        ..Default::default()
+                    },
+                ),
+                (
+                    0x9213,
+                    State {
+                        plm_population: 0x9100,
+                        enemy_population: 0x8000,
+                        enemy_set: 0x8000,
+                        fx: 0x8000,
+                        ..Default::default()
+                    },
+                ),
+            ]),
+            ..Default::default()
+        };
+        let save_station = Plm {
+            id: 0xB76F,
+            x: 0x05,
+            y: 0x0A,
+            argument: 0x0000,
+        };
+        let plm_population: Pc = LoRom { address: 0x8F9000 }.into();
+        sm.rom[plm_population.address..plm_population.address + 6]
+            .copy_from_slice(&save_station.to_bytes());
+        for (address, terminator) in [
+            (0xA18000, &[0xFF, 0xFF, 0x00][..]),
+            (0xB48000, &[0xFF, 0xFF]),
+            (0x838000, &[0xFF, 0xFF]),
+        ] {
+            let pc: Pc = LoRom { address }.into();
+            sm.rom[pc.address..pc.address + terminator.len()].copy_from_slice(terminator);
+        }
+        for address in [0x8FE000, 0xA1F000, 0xB4F000, 0x83F000] {
+            sm.free_space.free(LoRom { address }.into(), 0x20);
+        }
+
+        sm.load_plm_populations();
+        sm.load_enemies();
+        sm.load_fx();
+        assert_eq!(sm.plm_populations[&0x8F9000], [save_station]);
+        assert!(sm.plm_populations[&0x8F9100].is_empty());
+        assert_eq!(sm.asset_sizes[&0x8F9000], 8);
+        assert_eq!(sm.enemy_populations[&0xA18000], EnemyPopulation::default());
+        assert!(sm.enemy_sets[&0xB48000].is_empty());
+        assert!(sm.fx[&0x838000].is_empty());
+
+        // They still fit, so they are saved at the same address.
+        assert!(sm.save_plm_populations_to_rom().unwrap().is_empty());
+        assert!(sm.save_fx_to_rom().unwrap().is_empty());
+
+        // The second PLM population moves where the first one was.
+        sm.plm_populations
+            .get_mut(&0x8F9000)
+            .unwrap()
+            .push(save_station);
+        sm.plm_populations
+            .get_mut(&0x8F9100)
+            .unwrap()
+            .push(save_station);
+        sm.enemy_populations
+            .get_mut(&0xA18000)
+            .unwrap()
+            .enemies
+            .push(Enemy::default());
+        sm.enemy_sets
+            .get_mut(&0xB48000)
+            .unwrap()
+            .push(EnemySetEntry::default());
+        sm.fx.get_mut(&0x838000).unwrap().push(Fx {
+            fx_type: FxType::Water,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            sm.save_plm_populations_to_rom().unwrap(),
+            HashMap::from([(0x8F9000, 0x8FE000), (0x8F9100, 0x8F9000)])
+        );
+        assert_eq!(sm.plm_populations[&0x8FE000].len(), 2);
+        assert_eq!(sm.plm_populations[&0x8F9000].len(), 1);
+        assert_eq!(sm.states[&0x91F8].plm_population, 0xE000);
+        assert_eq!(sm.states[&0x9213].plm_population, 0x9000);
+
+        assert_eq!(
+            sm.save_enemy_populations_to_rom().unwrap(),
+            HashMap::from([(0xA18000, 0xA1F000)])
+        );
+        assert_eq!(
+            sm.save_enemy_sets_to_rom().unwrap(),
+            HashMap::from([(0xB48000, 0xB4F000)])
+        );
+        assert_eq!(
+            sm.save_fx_to_rom().unwrap(),
+            HashMap::from([(0x838000, 0x83F000)])
+        );
+        for state in sm.states.values() {
+            assert_eq!(
+                (state.enemy_population, state.enemy_set, state.fx),
+                (0xF000, 0xF000, 0xF000)
+            );
+        }
+
+        let enemy_set: Pc = LoRom { address: 0xB4F000 }.into();
+        assert_eq!(
+            &sm.rom[enemy_set.address..enemy_set.address + 6],
+            [0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF]
+        );
+        let fx_address: Pc = LoRom { address: 0x83F000 }.into();
+        assert_eq!(sm.rom[fx_address.address + 9], 0x06);
+    }
+
+    /// Load the species of the enemies, and save a change to a boss in-place.
+    #[test]
+    fn change_boss_health() {
+        let mut sm = SuperMetroid {
+            rom: vec![0; 0x200000],
+            enemy_populations: HashMap::from([(
+                0xA18000,
+                EnemyPopulation {
+                    enemies: vec![Enemy {
+                        species: 0xE2BF,
+                        ..Default::default()
+                    }],
+                    kill_count: 1,
+                },
+            )]),
+            ..Default::default()
+        };
+        let kraid: Pc = LoRom { address: 0xA0E2BF }.into();
+        sm.rom[kraid.address + 4..kraid.address + 6].copy_from_slice(&[0xE8, 0x03]);
+
+        sm.load_enemy_species();
+        assert_eq!(sm.enemy_species.len(), 1);
+        assert!(sm.boss_species_mut(Boss::Phantoon).is_none());
+
+        let kraid_species = sm.boss_species_mut(Boss::Kraid).unwrap();
+        assert_eq!(kraid_species.health, 1000);
+        kraid_species.health = 2000;
+        sm.save_enemy_species_to_rom();
+        assert_eq!(&sm.rom[kraid.address + 4..kraid.address + 6], [0xD0, 0x07]);
+    }
+
+    /// Read the tiles and palette of a species, from the pointers of its header.
+    #[test]
+    fn read_species_graphics() {
+        let mut sm = SuperMetroid {
+            rom: vec![0; 0x200000],
+            enemy_species: HashMap::from([(
+                0xA0D07F,
+                EnemySpecies {
+                    tile_data_size: 0x40,
+                    tile_data: 0xAB8000,
+                    ai_bank: 0xA2,
+                    palette: 0x8687,
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+        let tiles: Pc = LoRom { address: 0xAB8000 }.into();
+        sm.rom[tiles.address] = 0x80;
+        let palette: Pc = LoRom { address: 0xA28687 }.into();
+        sm.rom[palette.address + 2..palette.address + 4].copy_from_slice(&[0x1F, 0x00]);
+
+        let (graphics, palette) = sm.species_graphics(0xD07F).unwrap();
+        assert_eq!(graphics.tiles.len(), 2);
+        assert_eq!(graphics.tiles[0].colors[0], 0x01);
+        assert_eq!(palette.sub_palettes[0].colors[1].r, 0x1F);
+        assert!(sm.species_graphics(0xD0FF).is_none());
+    }
+
+    /// Follow the initialization AI of a species to the first spritemap it shows.
+    #[test]
+    fn read_species_spritemap() {
+        let mut sm = SuperMetroid {
+            rom: vec![0; 0x200000],
+            enemy_species: HashMap::from([(
+                0xA0D07F,
+                EnemySpecies {
+                    ai_bank: 0xA2,
+                    init_ai: 0x8000,
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        };
+        let init_ai: Pc = LoRom { address: 0xA28000 }.into();
+        sm.rom[init_ai.address..init_ai.address + 7]
+            .copy_from_slice(&[0xA9, 0x00, 0x90, 0x9D, 0x92, 0x0F, 0x6B]);
+        let instruction_list: Pc = LoRom { address: 0xA29000 }.into();
+        sm.rom[instruction_list.address..instruction_list.address + 4]
+            .copy_from_slice(&[0x0A, 0x00, 0x00, 0xA0]);
+        let spritemap: Pc = LoRom { address: 0xA2A000 }.into();
+        sm.rom[spritemap.address..spritemap.address + 7]
+            .copy_from_slice(&[0x01, 0x00, 0xFC, 0x01, 0xFC, 0x02, 0x21]);
+
+        let spritemap = sm.species_spritemap(0xD07F).unwrap();
+        assert_eq!(spritemap.len(), 1);
+        assert_eq!((spritemap[0].x, spritemap[0].y), (-4, -4));
+        assert_eq!(spritemap[0].tile, 0x102);
+        assert!(sm.species_spritemap(0xD0FF).is_none());
+    }
+
+    /// Read the name of a species from the ROM, or else from the known bosses.
+    #[test]
+    fn read_species_name() {
+        let mut sm = SuperMetroid {
+            rom: vec![0; 0x200000],
+            enemy_species: HashMap::from([
+                (
+                    0xA0D07F,
+                    EnemySpecies {
+                        name: 0xDD1F,
+                        ..Default::default()
+                    },
+                ),
+                (
+                    0xA0E2BF,
+                    EnemySpecies {
+                        name: 0xDD29,
+                        ..Default::default()
+                    },
+                ),
+            ]),
+            ..Default::default()
+        };
+        let name: Pc = LoRom { address: 0xB4DD1F }.into();
+        sm.rom[name.address..name.address + NAME_SIZE].copy_from_slice(b"ZEELA     ");
+
+        assert_eq!(sm.species_name(0xD07F).as_deref(), Some("ZEELA"));
+        assert_eq!(sm.species_name(0xE2BF).as_deref(), Some("Kraid"));
+        assert_eq!(sm.species_name(0xD0FF), None);
+    }
+
+    /// Save Save Stations in-place, and relocate them when an area has more of them.
+    /// The Debug ones of the last area are moved along, and every area loads back the same.
+    #[test]
+    fn save_save_stations_into_free_space() {
+        let mut save_stations = vec![Vec::new(); NUMBER_OF_AREAS];
+        save_stations[0].push(SaveStation {
+            room_pointer: 0x91F8,
+            ..Default::default()
+        });
+        save_stations[1].push(SaveStation {
+            room_pointer: 0x92FD,
+            ..Default::default()
+        });
+        save_stations[NUMBER_OF_AREAS - 1].push(SaveStation {
+            room_pointer: 0xDF45,
+            ..Default::default()
+        });
+        let mut sm = SuperMetroid {
+            rom: vec![0; 0x8000],
+            save_stations,
+            asset_sizes: HashMap::from([(0x80C4C5, SAVE_STATION_BYTE_SIZE * 3 + 2)]),
+            ..Default::default()
+        };
+        let list_address: Pc = LoRom {
+            address: SAVE_STATIONS_LIST,
+        }
+        .into();
+        sm.rom[list_address.address..list_address.address + 2].copy_from_slice(&[0xC5, 0xC4]);
+        sm.free_space
+            .free(LoRom { address: 0x80CD8E }.into(), 0x100);
+
+        let load_save_stations = |sm: &SuperMetroid| {
+            let list = &sm.rom[list_address.address..list_address.address + NUMBER_OF_AREAS * 2];
+            let first_area: Pc = LoRom {
+                address: SAVE_STATIONS_BANK + u16::from_le_bytes([list[0], list[1]]) as usize,
+            }
+            .into();
+            save_station::load_all_from_list(sm.rom.offset(first_area), list, NUMBER_OF_AREAS)
+                .unwrap()
+        };
+
+        sm.save_save_stations_to_rom().unwrap();
+        assert_eq!(
+            &sm.rom[list_address.address..list_address.address + 4],
+            [0xC5, 0xC4, 0xD3, 0xC4]
+        );
+        assert_eq!(
+            &sm.rom[list_address.address + 14..list_address.address + 16],
+            [0xE1, 0xC4]
+        );
+        assert_eq!(load_save_stations(&sm), sm.save_stations);
+
+        sm.save_stations[0].push(SaveStation {
+            room_pointer: 0x93AA,
+            ..Default::default()
+        });
+        sm.save_save_stations_to_rom().unwrap();
+        assert_eq!(
+            &sm.rom[list_address.address..list_address.address + 4],
+            [0x8E, 0xCD, 0xAA, 0xCD]
+        );
+        assert_eq!(
+            &sm.rom[list_address.address + 14..list_address.address + 16],
+            [0xB8, 0xCD]
+        );
+        assert_eq!(load_save_stations(&sm), sm.save_stations);
     }
 
     /// Get a Gfx with CRE.
@@ -0,0 +1,300 @@
+/// A Post-Load Modification: an object placed on a block of the Room, like items and door caps.
+/// Format reference: https://wiki.metroidconstruction.com/doku.php?id=super:technical_information:data_structures#plm_population
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Plm {
+    /// Pointer to the PLM header in bank $84.
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub id: u16,
+    /// Position in blocks.
+    pub x: u8,
+    pub y: u8,
+    /// Meaning depends on the PLM, e.g. the index of the item or door in the collected flags.
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub argument: u16,
+}
+
+/// Every PLM of a State, ended in ROM by a null id.
+pub type PlmPopulation = Vec<Plm>;
+
+pub const PLM_BYTE_SIZE: usize = 6;
+
+/// Items in the order of their PLMs, in each one of the styles.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Item {
+    EnergyTank,
+    Missile,
+    SuperMissile,
+    PowerBomb,
+    Bombs,
+    ChargeBeam,
+    IceBeam,
+    HiJumpBoots,
+    SpeedBooster,
+    WaveBeam,
+    Spazer,
+    SpringBall,
+    VariaSuit,
+    GravitySuit,
+    XRayScope,
+    PlasmaBeam,
+    GrappleBeam,
+    SpaceJump,
+    ScrewAttack,
+    MorphBall,
+    ReserveTank,
+}
+
+impl Item {
+    pub const ALL: [Item; 21] = [
+        Item::EnergyTank,
+        Item::Missile,
+        Item::SuperMissile,
+        Item::PowerBomb,
+        Item::Bombs,
+        Item::ChargeBeam,
+        Item::IceBeam,
+        Item::HiJumpBoots,
+        Item::SpeedBooster,
+        Item::WaveBeam,
+        Item::Spazer,
+        Item::SpringBall,
+        Item::VariaSuit,
+        Item::GravitySuit,
+        Item::XRayScope,
+        Item::PlasmaBeam,
+        Item::GrappleBeam,
+        Item::SpaceJump,
+        Item::ScrewAttack,
+        Item::MorphBall,
+        Item::ReserveTank,
+    ];
+}
+
+/// How an item is shown in the Room.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ItemStyle {
+    Visible,
+    /// Held by a Chozo statue, in an orb.
+    Chozo,
+    /// Inside a shot block.
+    Hidden,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DoorColor {
+    /// Opened by killing the enemies of the Room, or by some event.
+    Grey,
+    /// Opened by a Power Bomb.
+    Yellow,
+    /// Opened by a Super Missile.
+    Green,
+    /// Opened by five Missiles or a Super Missile.
+    Red,
+}
+
+/// Side of the block the door cap faces.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Facing {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// What a PLM is, for those known from the vanilla game.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PlmKind {
+    Item {
+        item: Item,
+        style: ItemStyle,
+    },
+    DoorCap {
+        color: DoorColor,
+        facing: Facing,
+    },
+    Gate,
+    SaveStation,
+    /// Scroll PLM changing the scrolls of the Room when touched, or an extension stretching it.
+    Scroll,
+    Other,
+}
+
+/// First PLM of each item style, followed by one PLM per item.
+const ITEMS: [(u16, ItemStyle); 3] = [
+    (0xEED7, ItemStyle::Visible),
+    (0xEF2B, ItemStyle::Chozo),
+    (0xEF7F, ItemStyle::Hidden),
+];
+/// First door cap PLM, followed by the ones of every color and facing, 6 bytes apart.
+const DOOR_CAPS: u16 = 0xC842;
+const DOOR_COLORS: [DoorColor; 4] = [
+    DoorColor::Grey,
+    DoorColor::Yellow,
+    DoorColor::Green,
+    DoorColor::Red,
+];
+const FACINGS: [Facing; 4] = [Facing::Left, Facing::Right, Facing::Up, Facing::Down];
+const GATES: [u16; 7] = [0xC826, 0xC82A, 0xC82E, 0xC832, 0xC836, 0xC83A, 0xC83E];
+const SAVE_STATION: u16 = 0xB76F;
+const SCROLLS: [u16; 5] = [0xB703, 0xB63B, 0xB63F, 0xB643, 0xB647];
+
+impl Plm {
+    pub fn kind(&self) -> PlmKind {
+        for (first, style) in ITEMS.iter() {
+            let index = self.id.wrapping_sub(*first) as usize;
+            if index.is_multiple_of(4) && index / 4 < Item::ALL.len() {
+                return PlmKind::Item {
+                    item: Item::ALL[index / 4],
+                    style: *style,
+                };
+            }
+        }
+
+        let index = self.id.wrapping_sub(DOOR_CAPS) as usize;
+        if index.is_multiple_of(6) && index / 6 < DOOR_COLORS.len() * FACINGS.len() {
+            return PlmKind::DoorCap {
+                color: DOOR_COLORS[index / 6 / FACINGS.len()],
+                facing: FACINGS[index / 6 % FACINGS.len()],
+            };
+        }
+
+        match self.id {
+            id if GATES.contains(&id) => PlmKind::Gate,
+            SAVE_STATION => PlmKind::SaveStation,
+            id if SCROLLS.contains(&id) => PlmKind::Scroll,
+            _ => PlmKind::Other,
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; PLM_BYTE_SIZE] {
+        let [id_low, id_high] = self.id.to_le_bytes();
+        let [argument_low, argument_high] = self.argument.to_le_bytes();
+        [id_low, id_high, self.x, self.y, argument_low, argument_high]
+    }
+}
+
+/// Load PLMs until the null id ending the population, or the end of 'source'.
+pub fn load_bytes(source: &[u8]) -> PlmPopulation {
+    source
+        .chunks_exact(PLM_BYTE_SIZE)
+        .map(|bytes| Plm {
+            id: u16::from_le_bytes([bytes[0], bytes[1]]),
+            x: bytes[2],
+            y: bytes[3],
+            argument: u16::from_le_bytes([bytes[4], bytes[5]]),
+        })
+        .take_while(|plm| plm.id != 0)
+        .collect()
+}
+
+/// Convert the PLMs into bytes, followed by the null id ending the population.
+/// PLMs with a null id are skipped, as they would end the population early.
+pub fn to_bytes(plm_population: &[Plm]) -> Vec<u8> {
+    plm_population
+        .iter()
+        .filter(|plm| plm.id != 0)
+        .flat_map(Plm::to_bytes)
+        .chain([0x00, 0x00])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Load a PLM population from bytes and convert it back.
+    #[test]
+    fn load_plm_population_from_bytes() {
+        #[rustfmt::skip]
+        let data = [
+            0x6F, 0xB7, 0x05, 0x0A, 0x00, 0x00, // Save Station
+            0x2F, 0xEF, 0x20, 0x2B, 0x0C, 0x00, // Chozo Missile
+            0x00, 0x00,                         // Terminator
+            0xFF, 0xFF,
+        ];
+
+        let plm_population = load_bytes(&data);
+        assert_eq!(
+            plm_population,
+            [
+                Plm {
+                    id: 0xB76F,
+                    x: 0x05,
+                    y: 0x0A,
+                    argument: 0x0000,
+                },
+                Plm {
+                    id: 0xEF2F,
+                    x: 0x20,
+                    y: 0x2B,
+                    argument: 0x000C,
+                },
+            ]
+        );
+        assert_eq!(to_bytes(&plm_population), data[..data.len() - 2]);
+        assert_eq!(
+            to_bytes(&[Plm::default(), plm_population[1]]),
+            data[PLM_BYTE_SIZE..data.len() - 2]
+        );
+        assert!(load_bytes(&[0x00, 0x00]).is_empty());
+    }
+
+    /// Classify items, door caps and other known PLMs by their id.
+    #[test]
+    fn classify_plms() {
+        let kind = |id| {
+            Plm {
+                id,
+                ..Default::default()
+            }
+            .kind()
+        };
+
+        assert_eq!(
+            kind(0xEED7),
+            PlmKind::Item {
+                item: Item::EnergyTank,
+                style: ItemStyle::Visible
+            }
+        );
+        assert_eq!(
+            kind(0xEF7B),
+            PlmKind::Item {
+                item: Item::ReserveTank,
+                style: ItemStyle::Chozo
+            }
+        );
+        assert_eq!(
+            kind(0xEFCF),
+            PlmKind::Item {
+                item: Item::ReserveTank,
+                style: ItemStyle::Hidden
+            }
+        );
+        assert_eq!(
+            kind(0xC842),
+            PlmKind::DoorCap {
+                color: DoorColor::Grey,
+                facing: Facing::Left
+            }
+        );
+        assert_eq!(
+            kind(0xC890),
+            PlmKind::DoorCap {
+                color: DoorColor::Red,
+                facing: Facing::Right
+            }
+        );
+        assert_eq!(kind(0xC82A), PlmKind::Gate);
+        assert_eq!(kind(0xB76F), PlmKind::SaveStation);
+        assert_eq!(kind(0xB703), PlmKind::Scroll);
+        assert_eq!(kind(0xEFD3), PlmKind::Other);
+        assert_eq!(kind(0xC845), PlmKind::Other);
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::HashSet;
+
+use super::address::NUMBER_OF_AREAS;
+
+/// Game events, by their bit in the event flags at $7E:D820.
+/// Reference: https://patrickjohnston.org/ASM/Lists/RAM%20map.asm
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Event {
+    ZebesAwake,
+    MotherBrainGlassBroken,
+    Zebetite1Destroyed,
+    Zebetite2Destroyed,
+    Zebetite3Destroyed,
+    PhantoonStatueGrey,
+    RidleyStatueGrey,
+    DraygonStatueGrey,
+    KraidStatueGrey,
+    TourianEntranceUnlocked,
+    MaridiaTubeBroken,
+    LowerNorfairAcidLowered,
+    ShaktoolClearedPath,
+    EscapeTimerSet,
+    CrittersEscaped,
+    FirstMetroidHallCleared,
+    FirstMetroidShaftCleared,
+    SecondMetroidHallCleared,
+    SecondMetroidShaftCleared,
+    OutranSpeedBoosterLavaquake,
+    /// An event bit not used by the vanilla game.
+    Other(u8),
+}
+
+impl Event {
+    /// Every event used by the vanilla game.
+    pub const ALL: [Event; 20] = [
+        Event::ZebesAwake,
+        Event::MotherBrainGlassBroken,
+        Event::Zebetite1Destroyed,
+        Event::Zebetite2Destroyed,
+        Event::Zebetite3Destroyed,
+        Event::PhantoonStatueGrey,
+        Event::RidleyStatueGrey,
+        Event::DraygonStatueGrey,
+        Event::KraidStatueGrey,
+        Event::TourianEntranceUnlocked,
+        Event::MaridiaTubeBroken,
+        Event::LowerNorfairAcidLowered,
+        Event::ShaktoolClearedPath,
+        Event::EscapeTimerSet,
+        Event::CrittersEscaped,
+        Event::FirstMetroidHallCleared,
+        Event::FirstMetroidShaftCleared,
+        Event::SecondMetroidHallCleared,
+        Event::SecondMetroidShaftCleared,
+        Event::OutranSpeedBoosterLavaquake,
+    ];
+
+    pub fn index(&self) -> u8 {
+        match self {
+            // Bit $13 is unused, so the last event skips it.
+            Event::OutranSpeedBoosterLavaquake => 0x14,
+            Event::Other(index) => *index,
+            event => Event::ALL.iter().position(|e| e == event).unwrap() as u8,
+        }
+    }
+}
+
+impl From<u8> for Event {
+    fn from(index: u8) -> Self {
+        Event::ALL
+            .iter()
+            .find(|event| event.index() == index)
+            .copied()
+            .unwrap_or(Event::Other(index))
+    }
+}
+
+/// Bosses, each one a bit of the boss flags of its area at $7E:D828.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Boss {
+    BombTorizo,
+    Kraid,
+    SporeSpawn,
+    Ridley,
+    Crocomire,
+    GoldenTorizo,
+    Phantoon,
+    Draygon,
+    Botwoon,
+    MotherBrain,
+}
+
+impl Boss {
+    pub const ALL: [Boss; 10] = [
+        Boss::BombTorizo,
+        Boss::Kraid,
+        Boss::SporeSpawn,
+        Boss::Ridley,
+        Boss::Crocomire,
+        Boss::GoldenTorizo,
+        Boss::Phantoon,
+        Boss::Draygon,
+        Boss::Botwoon,
+        Boss::MotherBrain,
+    ];
+
+    pub fn area(&self) -> u8 {
+        match self {
+            Boss::BombTorizo => 0,
+            Boss::Kraid | Boss::SporeSpawn => 1,
+            Boss::Ridley | Boss::Crocomire | Boss::GoldenTorizo => 2,
+            Boss::Phantoon => 3,
+            Boss::Draygon | Boss::Botwoon => 4,
+            Boss::MotherBrain => 5,
+        }
+    }
+
+    pub fn flag(&self) -> BossFlags {
+        match self {
+            Boss::Kraid | Boss::Ridley | Boss::Phantoon | Boss::Draygon | Boss::MotherBrain => {
+                BossFlags::AREA_BOSS
+            }
+            Boss::SporeSpawn | Boss::Crocomire | Boss::Botwoon => BossFlags::MINI_BOSS,
+            Boss::BombTorizo | Boss::GoldenTorizo => BossFlags::TORIZO,
+        }
+    }
+}
+
+/// Bitmask of bosses dead in one area, which boss each bit means depends on the area.
+#[derive(Debug, Default, Clone, Copy, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BossFlags(pub u8);
+
+impl BossFlags {
+    pub const AREA_BOSS: BossFlags = BossFlags(0x01);
+    pub const MINI_BOSS: BossFlags = BossFlags(0x02);
+    pub const TORIZO: BossFlags = BossFlags(0x04);
+
+    pub fn intersects(&self, other: BossFlags) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// The bosses of 'area' with a bit in these flags.
+    pub fn bosses(&self, area: u8) -> Vec<Boss> {
+        Boss::ALL
+            .iter()
+            .filter(|boss| boss.area() == area && self.intersects(boss.flag()))
+            .copied()
+            .collect()
+    }
+}
+
+/// What Samus has done so far, which decides the State each Room uses.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Progress {
+    pub events: HashSet<Event>,
+    pub boss_flags: [BossFlags; NUMBER_OF_AREAS],
+    /// Pointer of the Door Samus entered the Room through.
+    pub door: Option<u16>,
+    pub morph_ball: bool,
+    pub missiles: bool,
+    pub power_bombs: bool,
+    pub speed_booster: bool,
+}
+
+impl Progress {
+    pub fn set_boss_dead(&mut self, boss: Boss) {
+        self.boss_flags[boss.area() as usize].0 |= boss.flag().0;
+    }
+
+    pub fn is_boss_dead(&self, boss: Boss) -> bool {
+        self.boss_flags[boss.area() as usize].intersects(boss.flag())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Convert events from and into their bit index, keeping unknown ones.
+    #[test]
+    fn convert_events_from_and_into_index() {
+        assert_eq!(Event::from(0x00), Event::ZebesAwake);
+        assert_eq!(Event::from(0x0D), Event::EscapeTimerSet);
+        assert_eq!(Event::from(0x13), Event::Other(0x13));
+        assert_eq!(Event::from(0x14), Event::OutranSpeedBoosterLavaquake);
+
+        for index in 0..=0xFF {
+            assert_eq!(Event::from(index).index(), index);
+        }
+    }
+
+    /// Name the bosses of each area from their flags, and set them dead.
+    #[test]
+    fn bosses_from_area_flags() {
+        assert_eq!(BossFlags(0x01).bosses(3), [Boss::Phantoon]);
+        assert_eq!(
+            BossFlags(0x07).bosses(2),
+            [Boss::Ridley, Boss::Crocomire, Boss::GoldenTorizo]
+        );
+        assert!(BossFlags(0x02).bosses(3).is_empty());
+
+        let mut progress = Progress::default();
+        progress.set_boss_dead(Boss::Botwoon);
+        assert!(progress.is_boss_dead(Boss::Botwoon));
+        assert!(!progress.is_boss_dead(Boss::Draygon));
+        assert_eq!(progress.boss_flags[4], BossFlags::MINI_BOSS);
+    }
+}
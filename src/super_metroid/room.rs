@@ -1,9 +1,13 @@
-use crate::graphics::gfx::TILE_SIZE;
+use crate::{graphics::gfx::TILE_SIZE, ParseError};
 
-use super::level_data::BLOCKS_PER_SCREEN;
+use super::{
+    level_data::BLOCKS_PER_SCREEN,
+    progress::{BossFlags, Event, Progress},
+};
 
 /// Room format reference: https://wiki.metroidconstruction.com/doku.php?id=super:technical_information:data_structures#room_header
 #[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Room {
     pub index: u8,
     pub area: u8,
@@ -13,6 +17,7 @@ pub struct Room {
     pub up_scroller: u8,
     pub down_scroller: u8,
     pub cre_bitset: u8,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
     pub doors: u16,
     pub state_conditions: Vec<StateCondition>,
 }
@@ -36,6 +41,14 @@ impl Room {
         ]
     }
 
+    /// The State Condition the game picks given the 'progress', checked in ROM order.
+    pub fn active_state(&self, progress: &Progress) -> Option<&StateCondition> {
+        self.state_conditions
+            .iter()
+            .rev()
+            .find(|state_condition| state_condition.condition.is_met(self.area, progress))
+    }
+
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut output = Vec::new();
 
@@ -64,7 +77,14 @@ impl Room {
     }
 }
 
-pub fn from_bytes(room_address: u16, source: &[u8]) -> Room {
+/// Size of a Room header before its list of state conditions.
+const ROOM_HEADER_SIZE: usize = 11;
+
+pub fn from_bytes(room_address: u16, source: &[u8]) -> Result<Room, ParseError> {
+    if source.len() < ROOM_HEADER_SIZE {
+        return Err(ParseError::Truncated);
+    }
+
     #[rustfmt::skip]
     let room = Room {
         index:         source[0],
@@ -77,89 +97,206 @@ pub fn from_bytes(room_address: u16, source: &[u8]) -> Room {
         down_scroller: source[7],
         cre_bitset:    source[8],
         doors:         u16::from_le_bytes([source[9], source[10]]),
-        state_conditions: state_conditions_from_bytes(room_address + 11, &source[11..]),
+        state_conditions: state_conditions_from_bytes(
+            room_address.wrapping_add(ROOM_HEADER_SIZE as u16),
+            &source[ROOM_HEADER_SIZE..],
+        )?,
     };
-    room
+    Ok(room)
+}
+
+/// Check if 'source' looks like a Room header with a terminated list of state conditions.
+/// Used to validate Room pointers found while walking through Doors.
+pub fn is_valid_header(source: &[u8], number_of_areas: usize) -> bool {
+    if source.len() < 13
+        || source[1] as usize >= number_of_areas
+        || source[4] == 0
+        || source[5] == 0
+    {
+        return false;
+    }
+
+    let mut position = 11;
+    while let Some(bytes) = source.get(position..position + 2) {
+        position += match u16::from_le_bytes([bytes[0], bytes[1]]) {
+            DEFAULT => return true,
+            pointer if pointer >= 0x8000 => 2 + parameter_size(pointer) + 2,
+            _ => return false,
+        };
+    }
+    false
+}
+
+/// Pointers to the vanilla ASM routines checking each condition, in bank $8F.
+const DEFAULT: u16 = 0xE5E6;
+const DOOR_POINTER_IS: u16 = 0xE5EB;
+const MAIN_AREA_BOSS_DEAD: u16 = 0xE5FF;
+const EVENT_SET: u16 = 0xE612;
+const BOSS_DEAD: u16 = 0xE629;
+const MORPH_BALL: u16 = 0xE640;
+const MORPH_AND_MISSILES: u16 = 0xE652;
+const POWER_BOMBS: u16 = 0xE669;
+const SPEED_BOOSTER: u16 = 0xE676;
+
+/// What must be true for a Room to use a State, along with its parameter.
+/// Reference: https://patrickjohnston.org/bank/8F#fE5E6
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Condition {
+    /// Always true. It's the last condition checked, so it ends the list.
+    #[default]
+    Default,
+    /// Samus entered the Room through the Door at this pointer of bank $83.
+    DoorPointerIs(#[cfg_attr(feature = "serde", serde(with = "crate::hex"))] u16),
+    /// The boss of the Room's area is dead.
+    MainAreaBossDead,
+    EventSet(Event),
+    /// Any of the bosses of the Room's area in these flags is dead.
+    BossDead(BossFlags),
+    MorphBall,
+    MorphAndMissiles,
+    PowerBombs,
+    SpeedBooster,
+    /// A custom ASM routine, assumed to have no parameter.
+    Unknown(#[cfg_attr(feature = "serde", serde(with = "crate::hex"))] u16),
+}
+
+/// How many bytes of parameter follow the condition 'pointer'.
+fn parameter_size(pointer: u16) -> usize {
+    match pointer {
+        DOOR_POINTER_IS => 2,
+        EVENT_SET | BOSS_DEAD => 1,
+        _ => 0,
+    }
+}
+
+impl Condition {
+    /// Pointer to the ASM routine checking this condition.
+    pub fn pointer(&self) -> u16 {
+        match self {
+            Condition::Default => DEFAULT,
+            Condition::DoorPointerIs(_) => DOOR_POINTER_IS,
+            Condition::MainAreaBossDead => MAIN_AREA_BOSS_DEAD,
+            Condition::EventSet(_) => EVENT_SET,
+            Condition::BossDead(_) => BOSS_DEAD,
+            Condition::MorphBall => MORPH_BALL,
+            Condition::MorphAndMissiles => MORPH_AND_MISSILES,
+            Condition::PowerBombs => POWER_BOMBS,
+            Condition::SpeedBooster => SPEED_BOOSTER,
+            Condition::Unknown(pointer) => *pointer,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut output = self.pointer().to_le_bytes().to_vec();
+        match self {
+            Condition::DoorPointerIs(door) => output.extend(door.to_le_bytes()),
+            Condition::EventSet(event) => output.push(event.index()),
+            Condition::BossDead(flags) => output.push(flags.0),
+            _ => {}
+        }
+        output
+    }
+
+    /// Condition at the start of 'source', followed by its parameter.
+    pub fn from_bytes(source: &[u8]) -> Result<Condition, ParseError> {
+        let pointer = match source {
+            [low, high, ..] => u16::from_le_bytes([*low, *high]),
+            _ => return Err(ParseError::Truncated),
+        };
+        if source.len() < 2 + parameter_size(pointer) {
+            return Err(ParseError::Truncated);
+        }
+
+        Ok(match pointer {
+            DEFAULT => Condition::Default,
+            DOOR_POINTER_IS => Condition::DoorPointerIs(u16::from_le_bytes([source[2], source[3]])),
+            MAIN_AREA_BOSS_DEAD => Condition::MainAreaBossDead,
+            EVENT_SET => Condition::EventSet(Event::from(source[2])),
+            BOSS_DEAD => Condition::BossDead(BossFlags(source[2])),
+            MORPH_BALL => Condition::MorphBall,
+            MORPH_AND_MISSILES => Condition::MorphAndMissiles,
+            POWER_BOMBS => Condition::PowerBombs,
+            SPEED_BOOSTER => Condition::SpeedBooster,
+            pointer => Condition::Unknown(pointer),
+        })
+    }
+
+    /// Whether the condition is true in a Room of 'area' given the 'progress'.
+    /// Custom routines can't be checked, so they are never true.
+    pub fn is_met(&self, area: u8, progress: &Progress) -> bool {
+        let boss_flags = progress
+            .boss_flags
+            .get(area as usize)
+            .copied()
+            .unwrap_or_default();
+        match self {
+            Condition::Default => true,
+            Condition::DoorPointerIs(door) => progress.door == Some(*door),
+            Condition::MainAreaBossDead => boss_flags.intersects(BossFlags::AREA_BOSS),
+            Condition::EventSet(event) => progress.events.contains(event),
+            Condition::BossDead(flags) => boss_flags.intersects(*flags),
+            Condition::MorphBall => progress.morph_ball,
+            Condition::MorphAndMissiles => progress.morph_ball && progress.missiles,
+            Condition::PowerBombs => progress.power_bombs,
+            Condition::SpeedBooster => progress.speed_booster,
+            Condition::Unknown(_) => false,
+        }
+    }
 }
 
 /// Room format reference: https://wiki.metroidconstruction.com/doku.php?id=super:technical_information:data_structures#room_header
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StateCondition {
-    pub condition: u16,
-    pub parameter: Option<u16>,
+    pub condition: Condition,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
     pub state_address: u16,
 }
 
 impl StateCondition {
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut output = Vec::new();
-
-        output.extend(self.condition.to_le_bytes());
-        if let Some(parameter) = self.parameter {
-            if self.condition == 0xE5EB {
-                output.extend(parameter.to_le_bytes());
-            } else if self.condition == 0xE612 || self.condition == 0xE629 {
-                output.push(parameter as u8);
-            }
-        }
-        if self.condition != 0xE5E6 {
+        let mut output = self.condition.to_bytes();
+        // The default State comes right after the list, so it has no pointer to it.
+        if self.condition != Condition::Default {
             output.extend(self.state_address.to_le_bytes());
         }
-
         output
     }
 }
 
-fn state_conditions_from_bytes(default_state_address: u16, source: &[u8]) -> Vec<StateCondition> {
-    let condition = u16::from_le_bytes([source[0], source[1]]);
-
-    match condition {
-        // Terminator.
-        0xE5E6 => {
-            return Vec::from([StateCondition {
-                condition,
-                parameter: None,
-                state_address: default_state_address + 2,
-            }])
-        }
-        // Two bytes parameter.
-        0xE5EB => {
-            let mut states = state_conditions_from_bytes(default_state_address + 6, &source[6..]);
-            states.push(StateCondition {
-                condition,
-                parameter: Some(u16::from_le_bytes([source[2], source[3]])),
-                state_address: u16::from_le_bytes([source[4], source[5]]),
-            });
-            return states;
-        }
-        // One byte parameter.
-        0xE612 | 0xE629 => {
-            let mut states = state_conditions_from_bytes(default_state_address + 5, &source[5..]);
-            states.push(StateCondition {
-                condition,
-                parameter: Some(source[2] as u16),
-                state_address: u16::from_le_bytes([source[3], source[4]]),
-            });
-            return states;
-        }
-        // No parameter.
-        _ => {
-            let mut states = state_conditions_from_bytes(default_state_address + 4, &source[4..]);
-            states.push(StateCondition {
-                condition,
-                parameter: None,
-                state_address: u16::from_le_bytes([source[2], source[3]]),
-            });
-            return states;
-        }
-    };
+fn state_conditions_from_bytes(
+    default_state_address: u16,
+    source: &[u8],
+) -> Result<Vec<StateCondition>, ParseError> {
+    let condition = Condition::from_bytes(source)?;
+    if condition == Condition::Default {
+        return Ok(Vec::from([StateCondition {
+            condition,
+            state_address: default_state_address.wrapping_add(2),
+        }]));
+    }
+
+    let size = condition.to_bytes().len();
+    let state_address = source.get(size..size + 2).ok_or(ParseError::Truncated)?;
+    let mut states = state_conditions_from_bytes(
+        default_state_address.wrapping_add(size as u16 + 2),
+        &source[size + 2..],
+    )?;
+    states.push(StateCondition {
+        condition,
+        state_address: u16::from_le_bytes([state_address[0], state_address[1]]),
+    });
+    Ok(states)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::super_metroid::progress::Boss;
 
     /// Load a State Condition from bytes.
+    /// Convert the State Conditions back into bytes.
     #[test]
     fn load_state_condition_from_bytes() {
         #[rustfmt::skip]
@@ -181,36 +318,47 @@ mod tests {
 
         let expected_state_conditions = vec![
             StateCondition {
-                condition: 0xE5E6,
-                parameter: None,
+                condition: Condition::Default,
                 state_address: 38,
             },
             StateCondition {
-                condition: 0xE5EB,
-                parameter: Some(0xABCD),
+                condition: Condition::DoorPointerIs(0xABCD),
                 state_address: 0x2233,
             },
             StateCondition {
-                condition: 0xE612,
-                parameter: Some(0x42),
+                condition: Condition::EventSet(Event::Other(0x42)),
                 state_address: 0x4455,
             },
             StateCondition {
-                condition: 0xE629,
-                parameter: Some(0xF0),
+                condition: Condition::BossDead(BossFlags(0xF0)),
                 state_address: 0x6677,
             },
             StateCondition {
-                condition: 0xEDCB,
-                parameter: None,
+                condition: Condition::Unknown(0xEDCB),
                 state_address: 0xAABB,
             },
         ];
 
         assert_eq!(
-            state_conditions_from_bytes(16, &data),
+            state_conditions_from_bytes(16, &data).unwrap(),
             expected_state_conditions
         );
+        let bytes: Vec<u8> = expected_state_conditions
+            .iter()
+            .rev()
+            .flat_map(StateCondition::to_bytes)
+            .collect();
+        assert_eq!(bytes, data[..data.len() - 1]);
+
+        // A list cut before its terminator, or before a parameter.
+        assert_eq!(
+            state_conditions_from_bytes(16, &data[..data.len() - 3]),
+            Err(ParseError::Truncated)
+        );
+        assert_eq!(
+            Condition::from_bytes(&[0x12, 0xE6]),
+            Err(ParseError::Truncated)
+        );
     }
 
     /// Load a Room from bytes.
@@ -242,13 +390,76 @@ mod tests {
             cre_bitset:    0xFF,
             doors:         0x1234,
             state_conditions: vec![StateCondition{
-                                        condition: 0xE5E6,
-                                        parameter: None,
+                                        condition: Condition::Default,
                                         state_address: 20
                                     }
                                 ],
         };
 
-        assert_eq!(from_bytes(7, &data), expected_room);
+        assert_eq!(from_bytes(7, &data).unwrap(), expected_room);
+        assert_eq!(from_bytes(7, &data[..10]), Err(ParseError::Truncated));
+    }
+
+    /// Validate Room headers by their area, size and state conditions.
+    #[test]
+    fn validate_room_headers() {
+        #[rustfmt::skip]
+        let data = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x70, 0xA0, 0x00, 0x7B, 0x92, // Room header
+            0x12, 0xE6, 0x0E, 0x61, 0x92,                                     // State condition
+            0xE6, 0xE5,                                                       // Terminator
+        ];
+        assert!(is_valid_header(&data, 8));
+        assert!(!is_valid_header(&data, 1));
+        assert!(!is_valid_header(&data[..data.len() - 1], 8));
+
+        let mut data_without_width = data;
+        data_without_width[4] = 0;
+        assert!(!is_valid_header(&data_without_width, 8));
+
+        let mut data_with_invalid_condition = data;
+        data_with_invalid_condition[12] = 0x00;
+        assert!(!is_valid_header(&data_with_invalid_condition, 8));
+    }
+
+    /// Pick the State of a Room the game would use given the progress.
+    #[test]
+    fn pick_active_state() {
+        let room = Room {
+            area: 0x03,
+            state_conditions: vec![
+                StateCondition {
+                    condition: Condition::Default,
+                    state_address: 0x0001,
+                },
+                StateCondition {
+                    condition: Condition::EventSet(Event::ZebesAwake),
+                    state_address: 0x0002,
+                },
+                StateCondition {
+                    condition: Condition::BossDead(BossFlags::AREA_BOSS),
+                    state_address: 0x0003,
+                },
+                StateCondition {
+                    condition: Condition::Unknown(0xF000),
+                    state_address: 0x0004,
+                },
+            ],
+            ..Default::default()
+        };
+        let state_address =
+            |progress: &Progress| room.active_state(progress).unwrap().state_address;
+
+        let mut progress = Progress::default();
+        assert_eq!(state_address(&progress), 0x0001);
+
+        progress.events.insert(Event::ZebesAwake);
+        assert_eq!(state_address(&progress), 0x0002);
+
+        // Kraid is the area boss of Brinstar, not of the Wrecked Ship.
+        progress.set_boss_dead(Boss::Kraid);
+        assert_eq!(state_address(&progress), 0x0002);
+        progress.set_boss_dead(Boss::Phantoon);
+        assert_eq!(state_address(&progress), 0x0003);
     }
 }
@@ -1,7 +1,12 @@
+use crate::ParseError;
+
 /// Save Station format reference: https://patrickjohnston.org/bank/80#fC4B5
 #[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SaveStation {
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
     pub room_pointer: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
     pub door_pointer: u16,
     pub door_bts: u16,
     pub screen_x_position: u16,
@@ -26,32 +31,72 @@ impl SaveStation {
 
 pub const SAVE_STATION_BYTE_SIZE: usize = 14;
 
-// Load all Save Stations from one area until the next.
-// This does not load the Save Stations from the last area, which by default is the Debug ones.
+/// The Save Stations of the last area, by default the Debug ones, end with a $FFFF Room pointer.
+const TERMINATOR: u16 = 0xFFFF;
+
+// Load all Save Stations from one area until the next, and those of the last area until the terminator.
 pub fn load_all_from_list(
     bytes: &[u8],
     addresses: &[u8],
     number_of_areas: usize,
-) -> Vec<Vec<SaveStation>> {
-    let mut output = Vec::new();
+) -> Result<Vec<Vec<SaveStation>>, ParseError> {
+    if number_of_areas == 0 || addresses.len() < number_of_areas * 2 {
+        return Err(ParseError::Truncated);
+    }
 
     let addresses: Vec<usize> = addresses
-        .chunks(2)
+        .chunks_exact(2)
         .take(number_of_areas)
         .map(|address| u16::from_le_bytes([address[0], address[1]]) as usize)
         .collect();
 
-    let mut current_area_address = addresses[0];
-    for next_area_address in &addresses[1..] {
-        let mut area_stations = Vec::new();
-        while current_area_address != *next_area_address {
-            area_stations.push(load_bytes(&bytes[current_area_address - addresses[0]..]));
-            current_area_address += SAVE_STATION_BYTE_SIZE;
-        }
-        output.push(area_stations);
+    // Areas are listed in order, each starting on a Save Station boundary from the first one.
+    let offsets = addresses
+        .iter()
+        .map(|address| address.checked_sub(addresses[0]))
+        .collect::<Option<Vec<usize>>>()
+        .ok_or(ParseError::InvalidSize)?;
+    if offsets.windows(2).any(|area| area[0] > area[1])
+        || offsets
+            .iter()
+            .any(|offset| offset % SAVE_STATION_BYTE_SIZE != 0)
+    {
+        return Err(ParseError::InvalidSize);
+    }
+
+    let mut output = Vec::new();
+    for area in offsets.windows(2) {
+        let area_bytes = bytes.get(area[0]..area[1]).ok_or(ParseError::Truncated)?;
+        output.push(
+            area_bytes
+                .chunks_exact(SAVE_STATION_BYTE_SIZE)
+                .map(load_bytes)
+                .collect(),
+        );
     }
 
-    output
+    let last_area_bytes = bytes
+        .get(offsets[offsets.len() - 1]..)
+        .ok_or(ParseError::Truncated)?;
+    output.push(
+        last_area_bytes
+            .chunks_exact(SAVE_STATION_BYTE_SIZE)
+            .map(load_bytes)
+            .take_while(|save_station| save_station.room_pointer != TERMINATOR)
+            .collect(),
+    );
+
+    Ok(output)
+}
+
+/// Convert the Save Stations of every area into bytes, one area after another, followed by the terminator.
+pub fn to_bytes(save_stations: &[Vec<SaveStation>]) -> Vec<u8> {
+    save_stations
+        .iter()
+        .flatten()
+        .flat_map(SaveStation::to_bytes)
+        .chain(TERMINATOR.to_le_bytes())
+        .collect()
 }
 
 #[rustfmt::skip]
@@ -72,6 +117,7 @@ mod tests {
     use super::*;
 
     /// Load 3 Save Stations from bytes.
+    /// Convert Save Stations into bytes.
     #[test]
     fn load_stations_from_bytes() {
         #[rustfmt::skip]
@@ -112,9 +158,13 @@ mod tests {
 
         let save_stations = data.map(|bytes| load_bytes(&bytes));
         assert_eq!(save_stations, expected_save_stations);
+
+        for (save_station, bytes) in save_stations.iter().zip(data) {
+            assert_eq!(save_station.to_bytes(), bytes);
+        }
     }
 
-    /// Load 6 Save Stations divided in 3, 2 and 1 areas.
+    /// Load 7 Save Stations divided in 3, 2, 1 and 1 areas, the last one until the terminator.
     #[test]
     fn load_all_stations_from_bytes() {
         #[rustfmt::skip]
@@ -127,6 +177,10 @@ mod tests {
                     0x00, 0x00, 0x00, 0x00, 0xA8, 0x00, 0x00, 0x94, 0xCC, 0x8A, 0xBA, 0x00, 0x00, 0x00,
                     // 1 Save Station from Area 2.
                     0x00, 0x04, 0x00, 0x00, 0xB0, 0xAB, 0xCD, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04,
+                    // 1 Save Station from Area 3, the last one.
+                    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x04, 0x00, 0x00, 0xB0, 0xAB, 0xCD,
+                    // Terminator.
+                    0xFF, 0xFF,
                     ];
 
         #[rustfmt::skip]
@@ -191,6 +245,17 @@ mod tests {
                     samus_x_offset:    0x0400,
                 },
             ],
+            vec![
+                SaveStation {
+                    room_pointer:      0x0000,
+                    door_pointer:      0x0000,
+                    door_bts:          0x0000,
+                    screen_x_position: 0x0004,
+                    screen_y_position: 0x0004,
+                    samus_y_offset:    0xB000,
+                    samus_x_offset:    0xCDAB,
+                },
+            ],
         ];
 
         let save_station_list = vec![
@@ -205,7 +270,26 @@ mod tests {
                 acc
             });
 
-        let save_stations = load_all_from_list(&data, &save_station_list_bytes, 4);
+        let save_stations = load_all_from_list(&data, &save_station_list_bytes, 4).unwrap();
         assert_eq!(save_stations, expected_save_stations);
+        assert_eq!(to_bytes(&save_stations), data);
+    }
+
+    /// Fail on an area list or its Save Stations cut short, or on areas out of order, instead of panicking.
+    #[test]
+    fn load_truncated_save_stations() {
+        let list = [0x00, 0x00, 0x0E, 0x00];
+        assert_eq!(
+            load_all_from_list(&[0; 14], &list[..2], 2),
+            Err(ParseError::Truncated)
+        );
+        assert_eq!(
+            load_all_from_list(&[0; 13], &list, 2),
+            Err(ParseError::Truncated)
+        );
+        assert_eq!(
+            load_all_from_list(&[0; 28], &[0x0E, 0x00, 0x00, 0x00], 2),
+            Err(ParseError::InvalidSize)
+        );
     }
 }
@@ -0,0 +1,149 @@
+/// A sprite of a spritemap, placed relative to the position of what it draws.
+/// Format reference: https://patrickjohnston.org/ASM/ROM%20data/Super%20Metroid/Spritemap%20format.txt
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpritemapEntry {
+    /// Offset in pixels of the top-left of the sprite.
+    pub x: i16,
+    pub y: i8,
+    /// 16x16 pixels, made of four tiles, instead of a single 8x8 tile.
+    pub big: bool,
+    /// Index of the top-left tile, counted from the graphics of the species.
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
+    pub tile: u16,
+    /// Added to the sub palette of what it draws.
+    pub sub_palette: u8,
+    pub priority: u8,
+    pub x_flip: bool,
+    pub y_flip: bool,
+}
+
+/// Sprites drawn together as one frame, the first ones over the others.
+pub type Spritemap = Vec<SpritemapEntry>;
+
+pub const SPRITEMAP_ENTRY_BYTE_SIZE: usize = 5;
+
+/// Enemy RAM of the instruction list pointer, as written by 'STA $0F92,X'.
+const INSTRUCTION_LIST_RAM: [u8; 3] = [0x9D, 0x92, 0x0F];
+const LDA_IMMEDIATE: u8 = 0xA9;
+
+/// Load the sprites counted by the first word of 'source', or none if it's cut short.
+pub fn load_bytes(source: &[u8]) -> Spritemap {
+    let number_of_entries = match source {
+        [low, high, ..] => u16::from_le_bytes([*low, *high]) as usize,
+        _ => return Vec::new(),
+    };
+
+    match source.get(2..2 + number_of_entries * SPRITEMAP_ENTRY_BYTE_SIZE) {
+        Some(entries) => entries
+            .chunks_exact(SPRITEMAP_ENTRY_BYTE_SIZE)
+            .map(|bytes| {
+                let x = u16::from_le_bytes([bytes[0], bytes[1]]);
+                let properties = u16::from_le_bytes([bytes[3], bytes[4]]);
+                SpritemapEntry {
+                    // Sign extend the 9 bits of the offset.
+                    x: ((x << 7) as i16) >> 7,
+                    y: bytes[2] as i8,
+                    big: x & 0x8000 != 0,
+                    tile: properties & 0x01FF,
+                    sub_palette: ((properties >> 9) & 0x07) as u8,
+                    priority: ((properties >> 12) & 0x03) as u8,
+                    x_flip: properties & 0x4000 != 0,
+                    y_flip: properties & 0x8000 != 0,
+                }
+            })
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Find the instruction list an enemy starts with in the code of its initialization AI,
+/// from the first 'LDA #$xxxx : STA $0F92,X'. Lists chosen in other ways are not found.
+pub fn initial_instruction_list(init_ai: &[u8]) -> Option<u16> {
+    init_ai.windows(6).find_map(|code| match code {
+        [LDA_IMMEDIATE, low, high, store @ ..] if *store == INSTRUCTION_LIST_RAM => {
+            Some(u16::from_le_bytes([*low, *high]))
+        }
+        _ => None,
+    })
+}
+
+/// The spritemap shown first by 'instruction_list', if it starts by showing one.
+/// Each frame is a timer word followed by a spritemap pointer, while instructions are
+/// pointers to code, always above $8000.
+pub fn first_spritemap(instruction_list: &[u8]) -> Option<u16> {
+    match instruction_list {
+        [timer_low, timer_high, spritemap_low, spritemap_high, ..]
+            if u16::from_le_bytes([*timer_low, *timer_high]) < 0x8000 =>
+        {
+            Some(u16::from_le_bytes([*spritemap_low, *spritemap_high]))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Load a spritemap with a big flipped sprite and a small one at a negative offset.
+    #[test]
+    fn load_spritemap_from_bytes() {
+        #[rustfmt::skip]
+        let data = [
+            0x02, 0x00,             // Number of entries
+            0xF8, 0x81, 0xF0,       // x, size and y
+            0x00, 0x61,             // tile and properties
+            0x04, 0x00, 0x08,       // x, size and y
+            0x22, 0x33,             // tile and properties
+            0xAA,
+        ];
+
+        assert_eq!(
+            load_bytes(&data),
+            [
+                SpritemapEntry {
+                    x: -8,
+                    y: -16,
+                    big: true,
+                    tile: 0x100,
+                    sub_palette: 0,
+                    priority: 2,
+                    x_flip: true,
+                    y_flip: false,
+                },
+                SpritemapEntry {
+                    x: 4,
+                    y: 8,
+                    big: false,
+                    tile: 0x122,
+                    sub_palette: 1,
+                    priority: 3,
+                    x_flip: false,
+                    y_flip: false,
+                },
+            ]
+        );
+        assert!(load_bytes(&data[..8]).is_empty());
+    }
+
+    /// Find the first spritemap an enemy shows from the code of its initialization AI.
+    #[test]
+    fn find_first_spritemap_of_enemy() {
+        #[rustfmt::skip]
+        let init_ai = [
+            0xAE, 0x54, 0x0E,       // LDX $0E54
+            0xA9, 0x00, 0x00,       // LDA #$0000
+            0x9D, 0xA8, 0x0F,       // STA $0FA8,X
+            0xA9, 0x1F, 0xC3,       // LDA #$C31F
+            0x9D, 0x92, 0x0F,       // STA $0F92,X
+            0x6B,                   // RTL
+        ];
+        assert_eq!(initial_instruction_list(&init_ai), Some(0xC31F));
+        assert_eq!(initial_instruction_list(&init_ai[..14]), None);
+
+        assert_eq!(first_spritemap(&[0x0A, 0x00, 0x4D, 0xC1]), Some(0xC14D));
+        assert_eq!(first_spritemap(&[0x2F, 0x81, 0x4D, 0xC1]), None);
+        assert_eq!(first_spritemap(&[0x0A, 0x00]), None);
+    }
+}
@@ -1,26 +1,45 @@
+use crate::ParseError;
+
 //https://wiki.metroidconstruction.com/doku.php?id=super:technical_information:data_structures#state_header
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct State {
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
     pub level_address: u32, // Only three bytes are used (u24).
     pub tileset: u8,
     pub music_data_index: u8,
     pub music_track: u8,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
     pub fx: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
     pub enemy_population: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
     pub enemy_set: u16,
     pub layer_2_x_scroll: u8,
     pub layer_2_y_scroll: u8,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
     pub scroll: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
     pub special_x_ray_blocks: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
     pub main_asm: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
     pub plm_population: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
     pub library_background: u16,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
     pub setup_asm: u16,
 }
 
+pub const STATE_BYTE_SIZE: usize = 26;
+
 #[rustfmt::skip]
-pub fn load_bytes(source: &[u8]) -> State {
-    State {
+pub fn load_bytes(source: &[u8]) -> Result<State, ParseError> {
+    if source.len() < STATE_BYTE_SIZE {
+        return Err(ParseError::Truncated);
+    }
+
+    Ok(State {
         level_address:        u32::from_le_bytes([source[0], source[1], source[2], 0]),
         tileset:              source[3],
         music_data_index:     source[4],
@@ -36,11 +55,11 @@ pub fn load_bytes(source: &[u8]) -> State {
         plm_population:       u16::from_le_bytes([source[20], source[21]]),
         library_background:   u16::from_le_bytes([source[22], source[23]]),
         setup_asm:            u16::from_le_bytes([source[24], source[25]]),
-    }
+    })
 }
 
 impl State {
-    pub fn to_bytes(&self) -> [u8; 26] {
+    pub fn to_bytes(&self) -> [u8; STATE_BYTE_SIZE] {
         [
             self.level_address as u8,
             (self.level_address >> 8) as u8,
@@ -115,6 +134,10 @@ mod tests {
             setup_asm: 0xAABB,
         };
 
-        assert_eq!(load_bytes(&data), expected_state);
+        assert_eq!(load_bytes(&data).unwrap(), expected_state);
+        assert_eq!(
+            load_bytes(&data[..STATE_BYTE_SIZE - 1]),
+            Err(ParseError::Truncated)
+        );
     }
 }
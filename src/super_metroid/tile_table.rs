@@ -9,6 +9,7 @@ pub const BLOCK_SIZE: usize = TILE_SIZE * 2;
 pub type TileTable = Vec<Tile>;
 
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tile {
     pub y_flip: bool,
     pub x_flip: bool,
@@ -34,11 +35,26 @@ pub fn from_bytes(source: &[u8]) -> TileTable {
         .collect()
 }
 
+pub fn to_bytes(tile_table: &[Tile]) -> Vec<u8> {
+    tile_table
+        .iter()
+        .flat_map(|tile| {
+            ((tile.y_flip as u16) << 15
+                | (tile.x_flip as u16) << 14
+                | (tile.draw_behind_sprites as u16) << 13
+                | (tile.sub_palette as u16) << 10
+                | tile.gfx_index)
+                .to_le_bytes()
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     /// Load a tile table from bytes, each tile is two bytes.
+    /// Convert the tile table back into bytes.
     #[test]
     fn load_tile_table_from_bytes() {
         let data: Vec<u8> = [
@@ -81,5 +97,6 @@ mod tests {
         ];
 
         assert_eq!(from_bytes(&data), expected_tile_table);
+        assert_eq!(to_bytes(&expected_tile_table), data);
     }
 }
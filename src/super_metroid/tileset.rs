@@ -11,9 +11,13 @@ pub const TILESET_DATA_SIZE: usize = 9;
 pub const NUMBER_OF_TILESETS: usize = 0x1D;
 
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tileset {
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
     pub palette: u32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
     pub graphic: u32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::hex"))]
     pub tile_table: u32,
     pub use_cre: bool,
 }
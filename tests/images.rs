@@ -14,7 +14,7 @@ fn convert_super_metroid_palettes_to_image() {
     )
     .unwrap();
 
-    let remapped_palettes = sm.save_palettes_to_rom();
+    let remapped_palettes = sm.save_palettes_to_rom().unwrap();
 
     for (old_address, new_address) in remapped_palettes {
         let expected_image = image::open(format!(